@@ -0,0 +1,2 @@
+pub mod num;
+pub mod pool;
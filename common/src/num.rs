@@ -0,0 +1,250 @@
+/// Euclid's algorithm. `gcd(0, 0)` is 0.
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Stein's algorithm, trading division for shifts and subtraction.
+pub fn binary_gcd(mut a: u128, mut b: u128) -> u128 {
+    if a == 0 || b == 0 {
+        return a | b;
+    }
+    let shift = (a | b).trailing_zeros();
+    a >>= a.trailing_zeros();
+    while b != 0 {
+        b >>= b.trailing_zeros();
+        if a > b {
+            (a, b) = (b, a);
+        }
+        b -= a;
+    }
+    a << shift
+}
+
+/// `None` if the result doesn't fit in a `u128`.
+pub fn lcm(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// Returns `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+/// The coefficients are the minimal ones Euclid produces, so they never
+/// outgrow the inputs.
+pub fn ext_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, or `None` if `a` and `m`
+/// share a factor. Works for any `u128` modulus.
+pub fn mod_inverse(a: u128, m: u128) -> Option<u128> {
+    if m == 0 {
+        return None;
+    }
+    // Extended Euclid with the coefficient kept reduced mod m, so it stays
+    // unsigned
+    let (mut old_r, mut r) = (a % m, m);
+    let (mut old_s, mut s) = (1 % m, 0);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        let qs = mul_mod(q % m, s, m);
+        (old_s, s) = (s, sub_mod(old_s, qs, m));
+    }
+    if old_r == 1 || m == 1 {
+        Some(old_s)
+    } else {
+        None
+    }
+}
+
+/// `a * b % m` without overflowing.
+pub fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if let Some(p) = a.checked_mul(b) {
+        return p % m;
+    }
+    // Double and add
+    let (mut a, mut b, mut res) = (a % m, b, 0);
+    while b > 0 {
+        if b & 1 == 1 {
+            res = add_mod(res, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    res
+}
+
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+fn sub_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= b {
+        a - b
+    } else {
+        m - (b - a)
+    }
+}
+
+/// Merges `t ≡ a.0 (mod a.1)` and `t ≡ b.0 (mod b.1)` into one congruence.
+/// The moduli don't need to be coprime; `None` means no `t` satisfies both
+/// or the combined modulus doesn't fit in a `u128`.
+pub fn crt(a: (u128, u128), b: (u128, u128)) -> Option<(u128, u128)> {
+    let (r1, m1) = (a.0 % a.1, a.1);
+    let (r2, m2) = (b.0 % b.1, b.1);
+    let g = gcd(m1, m2);
+    let diff = sub_mod(r2, r1 % m2, m2);
+    if !diff.is_multiple_of(g) {
+        return None;
+    }
+
+    let n = m2 / g;
+    let m = lcm(m1, m2)?;
+    let inv = mod_inverse(m1 / g % n, n)?;
+    let k = mul_mod(diff / g % n, inv, n);
+    Some(((r1 + m1.checked_mul(k)?) % m, m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// xorshift, just to get spread-out inputs without pulling in a crate
+    fn numbers(count: usize) -> Vec<u128> {
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut res = Vec::new();
+        for _ in 0..count {
+            let mut next = || {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state
+            };
+            let n = ((next() as u128) << 64) | next() as u128;
+            // Mix in small and medium magnitudes too
+            res.push(n >> (next() % 128));
+        }
+        res
+    }
+
+    #[test]
+    fn gcd_test() {
+        let gcd_t = gcd(1071, 462);
+        let gcd_r = gcd(462, 1071);
+
+        assert_eq!(gcd_t, gcd_r);
+        assert_eq!(gcd_t, 21);
+    }
+
+    #[test]
+    fn lcm_test() {
+        let lcm1 = lcm(24, 36);
+        let lcm2 = lcm(11, 3);
+        let lcm3 = lcm(8, 10);
+
+        assert_eq!(lcm1, Some(72));
+        assert_eq!(lcm2, Some(33));
+        assert_eq!(lcm3, Some(40));
+        assert_eq!(
+            lcm(u64::MAX as u128, u64::MAX as u128 - 1),
+            Some(u64::MAX as u128 * (u64::MAX as u128 - 1))
+        );
+        assert_eq!(lcm(u128::MAX, u128::MAX - 1), None);
+    }
+
+    #[test]
+    fn gcd_matches_brute_force() {
+        for a in 0..80u128 {
+            for b in 0..80u128 {
+                let brute = (1..=a.max(b)).rev().find(|d| a % d == 0 && b % d == 0);
+                assert_eq!(gcd(a, b), brute.unwrap_or(0));
+                assert_eq!(binary_gcd(a, b), gcd(a, b));
+            }
+        }
+
+        let nums = numbers(400);
+        for pair in nums.chunks(2) {
+            assert_eq!(binary_gcd(pair[0], pair[1]), gcd(pair[0], pair[1]));
+        }
+    }
+
+    #[test]
+    fn lcm_matches_brute_force() {
+        for a in 1..40u128 {
+            for b in 1..40u128 {
+                let brute = (1..=a * b).find(|m| m % a == 0 && m % b == 0);
+                assert_eq!(lcm(a, b), brute);
+            }
+        }
+    }
+
+    #[test]
+    fn ext_gcd_identity() {
+        for pair in numbers(400).chunks(2) {
+            // Keep within i64 so a * x can't overflow when checking
+            let a = (pair[0] as u64 >> 1) as i128;
+            let b = -((pair[1] as u64 >> 1) as i128);
+            let (g, x, y) = ext_gcd(a, b);
+            assert_eq!(g, gcd(a.unsigned_abs(), b.unsigned_abs()) as i128);
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn mod_inverse_matches_brute_force() {
+        for m in 1..60u128 {
+            for a in 0..m {
+                let brute = (0..m).find(|x| a * x % m == 1 % m);
+                assert_eq!(mod_inverse(a, m), brute);
+            }
+        }
+
+        for pair in numbers(200).chunks(2) {
+            let m = pair[1] | 1;
+            if let Some(inv) = mod_inverse(pair[0], m) {
+                assert_eq!(mul_mod(pair[0], inv, m), 1 % m);
+            } else {
+                assert_ne!(gcd(pair[0], m), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn crt_test() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((2, 4), (4, 6)), Some((10, 12)));
+        assert_eq!(crt((1, 4), (2, 6)), None);
+
+        for m1 in 1..12u128 {
+            for m2 in 1..12u128 {
+                for r1 in 0..m1 {
+                    for r2 in 0..m2 {
+                        let brute = (0..m1 * m2).find(|t| t % m1 == r1 && t % m2 == r2);
+                        assert_eq!(crt((r1, m1), (r2, m2)).map(|c| c.0), brute);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{opposite, Grid, Maze, Point, DIRECTIONS};
+use std::collections::VecDeque;
+
+/// Step counts over pipe connections for every pipe in the maze.
+///
+/// Each connected network of pipes is searched breadth-first from its own
+/// root: the start tile for the network holding the start, and the first
+/// tile in reading order for the rest. Component 0 is always the start's.
+#[derive(Debug)]
+pub struct Distances {
+    steps: Grid<u32>,
+    component: Grid<u32>,
+    roots: Vec<Point>,
+    farthest: Vec<u32>,
+}
+
+impl Distances {
+    /// Steps from the tile's component root, `None` for ground.
+    pub fn steps(&self, p: Point) -> Option<u32> {
+        self.component(p).map(|_| self.steps[p])
+    }
+
+    pub fn component(&self, p: Point) -> Option<usize> {
+        let c = self.component[p];
+        (c != NO_COMPONENT).then_some(c as usize)
+    }
+
+    pub fn roots(&self) -> &[Point] {
+        &self.roots
+    }
+
+    /// Most steps from the root to any tile in `component`.
+    pub fn farthest(&self, component: usize) -> u32 {
+        self.farthest[component]
+    }
+}
+
+const NO_COMPONENT: u32 = u32::MAX;
+
+/// One bit per tile.
+struct Bitmap(Vec<u64>);
+
+impl Bitmap {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(64)])
+    }
+
+    fn get(&self, idx: usize) -> bool {
+        self.0[idx / 64] & (1 << (idx % 64)) != 0
+    }
+
+    fn set(&mut self, idx: usize) {
+        self.0[idx / 64] |= 1 << (idx % 64);
+    }
+}
+
+impl Maze {
+    /// The neighbour of `coords` in direction `dir`, if their pipes join up.
+    fn joined(&self, coords: Point, dir: u8) -> Option<Point> {
+        if self.tiles[coords].connections() & dir == 0 {
+            return None;
+        }
+        let n = self.tiles.neighbour(coords, dir)?;
+        (self.tiles[n].connections() & opposite(dir) != 0).then_some(n)
+    }
+
+    pub fn distances(&self) -> Distances {
+        let (width, height) = (self.tiles.width, self.tiles.height);
+        let mut res = Distances {
+            steps: Grid::filled(0, width, height),
+            component: Grid::filled(NO_COMPONENT, width, height),
+            roots: Vec::new(),
+            farthest: Vec::new(),
+        };
+        let mut visited = Bitmap::new(width * height);
+        let mut queue: VecDeque<Point> = VecDeque::new();
+
+        let roots = std::iter::once(self.start)
+            .chain((0..width * height).map(|idx| self.tiles.coords_from_idx(idx)));
+        for root in roots {
+            let idx = self.tiles.idx_from_coords(root);
+            if visited.get(idx) || self.tiles[root].connections() == 0 {
+                continue;
+            }
+
+            let component = res.roots.len() as u32;
+            let mut farthest = 0;
+            visited.set(idx);
+            queue.push_back(root);
+            while let Some(p) = queue.pop_front() {
+                let steps = res.steps[p];
+                res.component[p] = component;
+                farthest = farthest.max(steps);
+                for d in DIRECTIONS {
+                    let Some(n) = self.joined(p, d) else {
+                        continue;
+                    };
+                    let n_idx = self.tiles.idx_from_coords(n);
+                    if !visited.get(n_idx) {
+                        visited.set(n_idx);
+                        res.steps[n] = steps + 1;
+                        queue.push_back(n);
+                    }
+                }
+            }
+            res.roots.push(root);
+            res.farthest.push(farthest);
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn start_loop() {
+        let maze = Maze::parse("7-F7-\n.FJ|7\nSJLL7\n|F--J\nLJ.LJ").unwrap();
+        let dists = maze.distances();
+        assert_eq!(dists.roots()[0], maze.start);
+        assert_eq!(dists.farthest(0), 8);
+        assert_eq!(dists.steps(Point { x: 4, y: 2 }), Some(8));
+        assert_eq!(dists.steps(Point { x: 2, y: 4 }), None);
+    }
+
+    #[test]
+    fn other_components() {
+        let maze = Maze::parse("S7.F7-\nLJ.LJ.").unwrap();
+        let dists = maze.distances();
+        assert_eq!(dists.roots().len(), 3);
+        assert_eq!(dists.farthest(0), 2);
+
+        // The second loop is measured from its top-left corner
+        let corner = Point { x: 4, y: 1 };
+        assert_eq!(dists.component(corner), Some(1));
+        assert_eq!(dists.steps(corner), Some(2));
+        assert_eq!(dists.farthest(1), 2);
+
+        // A pipe that doesn't join anything is a component on its own
+        let lone = Point { x: 5, y: 0 };
+        assert_eq!(dists.component(lone), Some(2));
+        assert_eq!(dists.steps(lone), Some(0));
+    }
+}
@@ -0,0 +1,138 @@
+use crate::{EAST, NORTH, SOUTH, WEST};
+
+#[derive(Debug, Clone)]
+pub struct Grid<T> {
+    pub tiles: Vec<T>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(nodes: Vec<T>, width: usize, height: usize) -> Self {
+        Self {
+            tiles: nodes,
+            width,
+            height,
+        }
+    }
+
+    pub fn coords_in_bounds(&self, coords: Point) -> bool {
+        coords.x < self.width && coords.y < self.height
+    }
+
+    pub fn idx_from_coords(&self, coords: Point) -> usize {
+        (coords.y * self.width) + coords.x
+    }
+
+    pub fn coords_from_idx(&self, idx: usize) -> Point {
+        let y = idx / self.width;
+        let x = idx % self.width;
+        Point { x, y }
+    }
+
+    /// The tile one step from `coords` in direction `dir`, if it's on the grid.
+    pub fn neighbour(&self, coords: Point, dir: u8) -> Option<Point> {
+        let Point { x, y } = coords;
+        let res = match dir {
+            NORTH => Point {
+                x,
+                y: y.checked_sub(1)?,
+            },
+            SOUTH => Point { x, y: y + 1 },
+            EAST => Point { x: x + 1, y },
+            WEST => Point {
+                x: x.checked_sub(1)?,
+                y,
+            },
+            _ => return None,
+        };
+        self.coords_in_bounds(res).then_some(res)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(val: T, width: usize, height: usize) -> Self {
+        Self::from_vec(vec![val; width * height], width, height)
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl<T> std::ops::Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: Point) -> &Self::Output {
+        let idx = self.idx_from_coords(index);
+        &self.tiles[idx]
+    }
+}
+
+impl<T> std::ops::Index<usize> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.tiles[index]
+    }
+}
+
+impl<T> std::ops::IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, index: Point) -> &mut Self::Output {
+        let idx = self.idx_from_coords(index);
+        &mut self.tiles[idx]
+    }
+}
+
+impl<T> std::ops::IndexMut<usize> for Grid<T> {
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        &mut self.tiles[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_indexing() {
+        let v: Vec<u8> = (0..100).collect();
+        let grid: Grid<u8> = Grid::from_vec(v, 10, 10);
+        let nodes = &grid.tiles;
+
+        assert_eq!(nodes[0], 0);
+        assert_eq!(nodes[99], 99);
+        assert_eq!(nodes[57], 57);
+
+        let coords0 = grid.coords_from_idx(0);
+        let coords1 = grid.coords_from_idx(99);
+        let coords2 = grid.coords_from_idx(57);
+        assert_eq!(coords0, Point { x: 0, y: 0 });
+        assert_eq!(coords1, Point { x: 9, y: 9 });
+        assert_eq!(coords2, Point { x: 7, y: 5 });
+
+        assert_eq!(grid[coords0], 0);
+        assert_eq!(grid[coords1], 99);
+        assert_eq!(grid[coords2], 57);
+
+        let idx0 = grid.idx_from_coords(coords0);
+        let idx1 = grid.idx_from_coords(coords1);
+        let idx2 = grid.idx_from_coords(coords2);
+        assert_eq!(nodes[idx0], 0);
+        assert_eq!(nodes[idx1], 99);
+        assert_eq!(nodes[idx2], 57);
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid: Grid<u8> = Grid::filled(0, 3, 2);
+        let corner = Point { x: 0, y: 0 };
+        assert_eq!(grid.neighbour(corner, NORTH), None);
+        assert_eq!(grid.neighbour(corner, WEST), None);
+        assert_eq!(grid.neighbour(corner, EAST), Some(Point { x: 1, y: 0 }));
+        assert_eq!(grid.neighbour(corner, SOUTH), Some(Point { x: 0, y: 1 }));
+        assert_eq!(grid.neighbour(Point { x: 2, y: 1 }, SOUTH), None);
+    }
+}
@@ -0,0 +1,327 @@
+mod bfs;
+mod grid;
+mod render;
+
+pub use bfs::Distances;
+pub use grid::{Grid, Point};
+pub use render::{box_char, Render, Style};
+
+pub const NORTH: u8 = 0b0000_0010;
+pub const SOUTH: u8 = 0b0000_0100;
+pub const EAST: u8 = 0b0000_1000;
+pub const WEST: u8 = 0b0001_0000;
+pub const DIRECTIONS: [u8; 4] = [NORTH, SOUTH, EAST, WEST];
+
+pub fn opposite(dir: u8) -> u8 {
+    match dir {
+        NORTH => SOUTH,
+        SOUTH => NORTH,
+        EAST => WEST,
+        WEST => EAST,
+        _ => unreachable!(),
+    }
+}
+
+/// Each pipe is the set of directions it connects, so `NE` is `NORTH | EAST`.
+#[repr(u8)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Ground = 0,
+    NS = 0b0000_0110,
+    EW = 0b0001_1000,
+    NE = 0b0000_1010,
+    NW = 0b0001_0010,
+    SW = 0b0001_0100,
+    SE = 0b0000_1100,
+}
+
+impl Tile {
+    pub fn connections(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_connections(dirs: u8) -> Option<Self> {
+        [Self::NS, Self::EW, Self::NE, Self::NW, Self::SW, Self::SE]
+            .into_iter()
+            .find(|t| *t as u8 == dirs)
+    }
+}
+
+/// The start tile `S` has no shape of its own until the maze is parsed, so
+/// it's left out here.
+pub fn tile_from_char(c: char) -> Result<Tile, String> {
+    match c {
+        '|' => Ok(Tile::NS),
+        '-' => Ok(Tile::EW),
+        'L' => Ok(Tile::NE),
+        'J' => Ok(Tile::NW),
+        '7' => Ok(Tile::SW),
+        'F' => Ok(Tile::SE),
+        '.' => Ok(Tile::Ground),
+        _ => Err(format!("Invalid tile '{c}'")),
+    }
+}
+
+#[derive(Debug)]
+pub struct Maze {
+    pub tiles: Grid<Tile>,
+    pub start: Point,
+}
+
+impl Maze {
+    pub fn parse(input: &'_ str) -> Result<Self, String> {
+        let mut tiles: Vec<Tile> = Vec::new();
+        let mut start: Option<Point> = None;
+        let mut width = 0;
+        let mut height = 0;
+
+        for line in input.lines().map(|l| l.trim_end()) {
+            if line.is_empty() {
+                continue;
+            }
+            let mut len = 0;
+            for (x, c) in line.chars().enumerate() {
+                if c == 'S' {
+                    if start.is_some() {
+                        return Err(format!("line {}: second start tile", height + 1));
+                    }
+                    start = Some(Point { x, y: height });
+                    tiles.push(Tile::Ground);
+                } else {
+                    let t = tile_from_char(c).map_err(|e| format!("line {}: {e}", height + 1))?;
+                    tiles.push(t);
+                }
+                len += 1;
+            }
+            if height == 0 {
+                width = len;
+            } else if len != width {
+                return Err(format!(
+                    "line {}: expected {width} tiles, found {len}",
+                    height + 1
+                ));
+            }
+            height += 1;
+        }
+
+        let start = start.ok_or("No start tile".to_string())?;
+        let mut res = Self {
+            tiles: Grid::from_vec(tiles, width, height),
+            start,
+        };
+        res.tiles[start] = res.infer_start()?;
+        Ok(res)
+    }
+
+    /// Works out which pipe is under `S` from the neighbours that point at it.
+    fn infer_start(&self) -> Result<Tile, String> {
+        let mut dirs = 0;
+        for d in DIRECTIONS {
+            if self.connects_back(self.start, d) {
+                dirs |= d;
+            }
+        }
+        Tile::from_connections(dirs).ok_or(format!(
+            "Start tile connects to {} pipes, expected 2",
+            dirs.count_ones()
+        ))
+    }
+
+    /// Whether the tile next to `coords` in direction `dir` has a pipe
+    /// pointing back.
+    fn connects_back(&self, coords: Point, dir: u8) -> bool {
+        match self.tiles.neighbour(coords, dir) {
+            Some(n) => self.tiles[n].connections() & opposite(dir) != 0,
+            None => false,
+        }
+    }
+
+    /// Follows the pipes out of the start tile and back again.
+    pub fn trace_loop(&self) -> Result<Loop, String> {
+        let start = self.start;
+        let mut tiles: Vec<Point> = Vec::new();
+        let mut vertices: Vec<Point> = Vec::new();
+        let mut coords = start;
+        // Leave by the lowest direction bit of the start pipe
+        let pipe = self.tiles[start].connections();
+        let mut dir = pipe & pipe.wrapping_neg();
+        loop {
+            tiles.push(coords);
+            coords = self
+                .tiles
+                .neighbour(coords, dir)
+                .ok_or(format!("Loop runs off the map at {coords:?}"))?;
+
+            let pipe = self.tiles[coords].connections();
+            if pipe & opposite(dir) == 0 {
+                return Err(format!("Loop is broken at {coords:?}"));
+            }
+            let next = pipe & !opposite(dir);
+            if next != dir {
+                vertices.push(coords);
+            }
+            dir = next;
+
+            if coords == start {
+                break;
+            }
+        }
+        Ok(Loop { tiles, vertices })
+    }
+
+    /// One flag per tile, set where the tile is part of `pipe_loop`.
+    pub fn loop_mask(&self, pipe_loop: &Loop) -> Grid<bool> {
+        let mut mask = Grid::filled(false, self.tiles.width, self.tiles.height);
+        for p in pipe_loop.tiles.iter() {
+            mask[*p] = true;
+        }
+        mask
+    }
+
+    /// Every tile strictly inside `pipe_loop`, in reading order.
+    ///
+    /// A ray cast west along a row is inside the loop after crossing an odd
+    /// number of loop pipes. Counting only pipes that reach north means
+    /// `L-7` counts as one crossing and `L-J` as two, which squeezes
+    /// between pipes without needing a doubled-up grid.
+    pub fn enclosed_tiles(&self, pipe_loop: &Loop) -> Vec<Point> {
+        let on_loop = self.loop_mask(pipe_loop);
+        let mut res = Vec::new();
+        for y in 0..self.tiles.height {
+            let mut inside = false;
+            for x in 0..self.tiles.width {
+                let p = Point { x, y };
+                if on_loop[p] {
+                    if self.tiles[p].connections() & NORTH != 0 {
+                        inside = !inside;
+                    }
+                } else if inside {
+                    res.push(p);
+                }
+            }
+        }
+        res
+    }
+}
+
+/// A closed pipe loop, with its tiles in the order they're walked from the
+/// start.
+#[derive(Debug)]
+pub struct Loop {
+    pub tiles: Vec<Point>,
+    /// The corners of the loop, in walk order.
+    pub vertices: Vec<Point>,
+}
+
+impl Loop {
+    /// Steps to the tile farthest from the start, going either way round.
+    pub fn farthest(&self) -> usize {
+        self.tiles.len() / 2
+    }
+
+    /// Area enclosed by the loop's centre line, by the shoelace formula.
+    pub fn area(&self) -> usize {
+        let v = &self.vertices;
+        let mut a: isize = 0;
+        let mut b: isize = 0;
+        for i in 0..v.len() {
+            let n = if (i + 1) < v.len() { i + 1 } else { 0 };
+            a += v[i].x as isize * v[n].y as isize;
+            b += v[i].y as isize * v[n].x as isize;
+        }
+        (a - b).unsigned_abs() / 2
+    }
+
+    /// Tiles fully inside the loop, by Pick's theorem.
+    pub fn interior_count(&self) -> usize {
+        self.area() + 1 - (self.tiles.len() / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FARTHEST: &str = "..F7.
+.FJ|.
+SJ.L7
+|F--J
+LJ...";
+
+    const ENCLOSED: &str = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........";
+
+    #[test]
+    fn farthest() {
+        let maze = Maze::parse(FARTHEST).unwrap();
+        let pipe_loop = maze.trace_loop().unwrap();
+        assert_eq!(pipe_loop.tiles.len(), 16);
+        assert_eq!(pipe_loop.farthest(), 8);
+    }
+
+    #[test]
+    fn enclosed() {
+        let maze = Maze::parse(ENCLOSED).unwrap();
+        let pipe_loop = maze.trace_loop().unwrap();
+        assert_eq!(pipe_loop.vertices.len(), 12);
+        assert_eq!(pipe_loop.interior_count(), 4);
+
+        let enclosed = maze.enclosed_tiles(&pipe_loop);
+        let expected: Vec<Point> = [(2, 6), (3, 6), (7, 6), (8, 6)]
+            .into_iter()
+            .map(|(x, y)| Point { x, y })
+            .collect();
+        assert_eq!(enclosed, expected);
+    }
+
+    #[test]
+    fn enclosed_matches_picks() {
+        let maze = Maze::parse(
+            ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...",
+        )
+        .unwrap();
+        let pipe_loop = maze.trace_loop().unwrap();
+        assert_eq!(pipe_loop.interior_count(), 8);
+        assert_eq!(maze.enclosed_tiles(&pipe_loop).len(), 8);
+    }
+
+    #[test]
+    fn bad_mazes() {
+        assert!(Maze::parse("...\n.|.").is_err());
+        assert!(Maze::parse("..\n.").is_err());
+        assert!(Maze::parse("S-7\n|.|\nL-.").unwrap().trace_loop().is_err());
+        assert!(Maze::parse("...\n.S.\n...").is_err());
+        assert!(Maze::parse(".|.\n-S-\n.|.").is_err());
+    }
+
+    #[test]
+    fn start_shape() {
+        let maze = Maze::parse(FARTHEST).unwrap();
+        assert_eq!(maze.tiles[maze.start], Tile::SE);
+
+        // A straight start isn't a corner
+        let maze = Maze::parse(".....\n.F-7.\n.S.|.\n.L-J.\n.....").unwrap();
+        assert_eq!(maze.tiles[maze.start], Tile::NS);
+        let pipe_loop = maze.trace_loop().unwrap();
+        assert!(!pipe_loop.vertices.contains(&maze.start));
+        assert_eq!(pipe_loop.vertices.len(), 4);
+        assert_eq!(pipe_loop.area(), 4);
+        assert_eq!(pipe_loop.interior_count(), 1);
+    }
+}
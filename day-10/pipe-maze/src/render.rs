@@ -0,0 +1,136 @@
+use crate::{Grid, Loop, Maze, Tile};
+use std::str::FromStr;
+
+const DIM: &str = "\x1b[2m";
+const BOLD: &str = "\x1b[1m";
+const INSIDE_BG: &str = "\x1b[42m";
+const OUTSIDE_BG: &str = "\x1b[44m";
+const RESET: &str = "\x1b[0m";
+
+/// How a rendered maze is decorated.
+///
+/// `Plain` is stable text for snapshots: loop pipes as box-drawing
+/// characters and everything else as `I` or `O` for inside or outside the
+/// loop. `Ansi` keeps junk pipes visible but dimmed, and shades the inside
+/// and outside with background colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Plain,
+    Ansi,
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Self::Plain),
+            "ansi" => Ok(Self::Ansi),
+            _ => Err(format!("Unknown style '{s}', expected plain or ansi")),
+        }
+    }
+}
+
+pub fn box_char(tile: Tile) -> char {
+    match tile {
+        Tile::NS => '│',
+        Tile::EW => '─',
+        Tile::NE => '└',
+        Tile::NW => '┘',
+        Tile::SW => '┐',
+        Tile::SE => '┌',
+        Tile::Ground => ' ',
+    }
+}
+
+/// Redraws a maze around one of its loops.
+pub struct Render<'a> {
+    maze: &'a Maze,
+    on_loop: Grid<bool>,
+    inside: Grid<bool>,
+}
+
+impl<'a> Render<'a> {
+    pub fn new(maze: &'a Maze, pipe_loop: &Loop) -> Self {
+        let mut inside = Grid::filled(false, maze.tiles.width, maze.tiles.height);
+        for p in maze.enclosed_tiles(pipe_loop) {
+            inside[p] = true;
+        }
+        Self {
+            maze,
+            on_loop: maze.loop_mask(pipe_loop),
+            inside,
+        }
+    }
+
+    pub fn render(&self, style: Style) -> String {
+        let tiles = &self.maze.tiles;
+        let mut out = String::new();
+        for y in 0..tiles.height {
+            for x in 0..tiles.width {
+                let idx = y * tiles.width + x;
+                let tile = tiles[idx];
+                let inside = self.inside[idx];
+                if self.on_loop[idx] {
+                    match style {
+                        Style::Plain => out.push(box_char(tile)),
+                        Style::Ansi => {
+                            out.push_str(BOLD);
+                            out.push(box_char(tile));
+                            out.push_str(RESET);
+                        }
+                    }
+                    continue;
+                }
+
+                match style {
+                    Style::Plain => out.push(if inside { 'I' } else { 'O' }),
+                    Style::Ansi => {
+                        out.push_str(if inside { INSIDE_BG } else { OUTSIDE_BG });
+                        out.push_str(DIM);
+                        out.push(box_char(tile));
+                        out.push_str(RESET);
+                    }
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_snapshot() {
+        let maze = Maze::parse(
+            "...........
+.S-------7.
+.|F-----7|.
+.||..-..||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........",
+        )
+        .unwrap();
+        let pipe_loop = maze.trace_loop().unwrap();
+        let expected = "OOOOOOOOOOO
+O┌───────┐O
+O│┌─────┐│O
+O││OOOOO││O
+O││OOOOO││O
+O│└─┐O┌─┘│O
+O│II│O│II│O
+O└──┘O└──┘O
+OOOOOOOOOOO
+";
+        let render = Render::new(&maze, &pipe_loop);
+        assert_eq!(render.render(Style::Plain), expected);
+        // The junk pipe is still drawn, just dimmed
+        assert!(render.render(Style::Ansi).contains("\x1b[44m\x1b[2m─"));
+    }
+}
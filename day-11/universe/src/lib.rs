@@ -0,0 +1,286 @@
+mod query;
+
+pub use query::{parse_pair, Neighbours, MAX_DRAW};
+
+use common::pool::Pool;
+use std::str::FromStr;
+
+/// How many lines each empty column and row turns into. Parsed as `N` for
+/// the same factor both ways, or `CxR` for `C` per empty column and `R`
+/// per empty row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expansion {
+    pub columns: u64,
+    pub rows: u64,
+}
+
+impl Expansion {
+    pub fn uniform(factor: u64) -> Self {
+        Self {
+            columns: factor,
+            rows: factor,
+        }
+    }
+}
+
+impl FromStr for Expansion {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let factor = |f: &str| {
+            f.trim()
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid expansion factor '{f}'"))
+        };
+        match s.split_once('x') {
+            Some((columns, rows)) => Ok(Self {
+                columns: factor(columns)?,
+                rows: factor(rows)?,
+            }),
+            None => Ok(Self::uniform(factor(s)?)),
+        }
+    }
+}
+
+/// A galaxy image before expansion. Galaxy coordinates are as read, and
+/// the empty rows and columns are kept separately so any expansion factor
+/// can be applied later.
+#[derive(Debug)]
+pub struct Universe {
+    pub galaxies: Vec<(u64, u64)>,
+    pub width: u64,
+    pub height: u64,
+    pub empty_rows: Vec<u64>,
+    pub empty_columns: Vec<u64>,
+}
+
+impl Universe {
+    pub fn parse(input: &'_ str) -> Result<Self, String> {
+        let mut galaxies: Vec<(u64, u64)> = Vec::new();
+        let mut width = 0;
+        let mut height = 0;
+        let mut empty_rows: Vec<u64> = Vec::new();
+        let mut column_used: Vec<bool> = Vec::new();
+
+        for line in input.lines().map(|l| l.trim_end()) {
+            if line.is_empty() {
+                continue;
+            }
+            if height == 0 {
+                width = line.len() as u64;
+                column_used = vec![false; line.len()];
+            } else if line.len() as u64 != width {
+                return Err(format!(
+                    "line {}: expected {width} columns, found {}",
+                    height + 1,
+                    line.len()
+                ));
+            }
+
+            let mut empty = true;
+            for (x, c) in line.char_indices() {
+                match c {
+                    '#' => {
+                        galaxies.push((x as u64, height));
+                        column_used[x] = true;
+                        empty = false;
+                    }
+                    '.' => (),
+                    _ => return Err(format!("line {}: invalid character '{c}'", height + 1)),
+                }
+            }
+            if empty {
+                empty_rows.push(height);
+            }
+            height += 1;
+        }
+
+        let empty_columns = (0..width).filter(|x| !column_used[*x as usize]).collect();
+        Ok(Self {
+            galaxies,
+            width,
+            height,
+            empty_rows,
+            empty_columns,
+        })
+    }
+
+    /// Galaxy coordinates once every empty column and row has been
+    /// replaced by as many as `expansion` says.
+    ///
+    /// Every coordinate is inside the expanded map, and no distance is
+    /// longer than its width and height together, so checking those once
+    /// means nothing measured on the result can overflow.
+    pub fn expanded(&self, expansion: Expansion) -> Result<Vec<(u64, u64)>, String> {
+        let (width, height) = self.expanded_size(expansion)?;
+        width
+            .checked_add(height)
+            .ok_or_else(|| too_big(expansion))?;
+        Ok(self
+            .galaxies
+            .iter()
+            .map(|(x, y)| {
+                (
+                    expand(*x, &self.empty_columns, expansion.columns),
+                    expand(*y, &self.empty_rows, expansion.rows),
+                )
+            })
+            .collect())
+    }
+
+    /// Sum of the shortest distances between every pair of galaxies.
+    ///
+    /// Expansion only stretches rows and columns, so each distance is the
+    /// Manhattan distance between expanded coordinates, and the two axes
+    /// can be summed separately.
+    pub fn total_distance(&self, expansion: Expansion) -> Result<u64, String> {
+        let expanded = self.expanded(expansion)?;
+        let xs: Vec<u64> = expanded.iter().map(|g| g.0).collect();
+        let ys: Vec<u64> = expanded.iter().map(|g| g.1).collect();
+        fit_total(axis_total(xs) + axis_total(ys))
+    }
+
+    /// The same total, measured pair by pair across `pool`. Each job sums
+    /// one galaxy's distances to the galaxies after it.
+    pub fn pairwise_total(&self, expansion: Expansion, pool: &Pool) -> Result<u64, String> {
+        let g = self.expanded(expansion)?;
+        let idx: Vec<usize> = (0..g.len()).collect();
+        let total = pool
+            .map(&idx, |i| {
+                g[(i + 1)..]
+                    .iter()
+                    .map(|o| (g[*i].0.abs_diff(o.0) + g[*i].1.abs_diff(o.1)) as u128)
+                    .sum::<u128>()
+            })
+            .iter()
+            .sum();
+        fit_total(total)
+    }
+}
+
+pub(crate) fn too_big(expansion: Expansion) -> String {
+    format!(
+        "Expansion {}x{} is too big for this map",
+        expansion.columns, expansion.rows
+    )
+}
+
+/// Totals are added up as `u128`, which any number of `u64` distances
+/// between real galaxies fits in.
+fn fit_total(total: u128) -> Result<u64, String> {
+    u64::try_from(total).map_err(|_| format!("Total distance {total} overflows u64"))
+}
+
+/// Where `coord` ends up once each empty line before it turns into
+/// `factor` lines. A factor of 0 squeezes the empty lines out entirely.
+/// Only called once the expanded map is known to fit in `u64`.
+fn expand(coord: u64, empty: &[u64], factor: u64) -> u64 {
+    let before = empty.partition_point(|e| *e < coord) as u64;
+    coord - before + before * factor
+}
+
+/// Sum of `|a - b|` over every pair. Once sorted, the `i`th coordinate is
+/// at least as big as the `i` before it, so it contributes `i * c` less
+/// their running total.
+fn axis_total(mut coords: Vec<u64>) -> u128 {
+    coords.sort_unstable();
+    let mut total = 0;
+    let mut prefix = 0;
+    for (i, c) in coords.iter().enumerate() {
+        let c = *c as u128;
+        total += i as u128 * c - prefix;
+        prefix += c;
+    }
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn parse() {
+        let universe = Universe::parse(EXAMPLE).unwrap();
+        assert_eq!(universe.galaxies.len(), 9);
+        assert_eq!(universe.empty_rows, vec![3, 7]);
+        assert_eq!(universe.empty_columns, vec![2, 5, 8]);
+        assert!(Universe::parse("#.\n#").is_err());
+        assert!(Universe::parse("#x").is_err());
+    }
+
+    #[test]
+    fn totals() {
+        let universe = Universe::parse(EXAMPLE).unwrap();
+        let total = |e: &str| universe.total_distance(e.parse().unwrap());
+        assert_eq!(total("2"), Ok(374));
+        assert_eq!(total("10"), Ok(1030));
+        assert_eq!(total("100"), Ok(8410));
+        assert_eq!(total("1"), Ok(292));
+    }
+
+    #[test]
+    fn per_axis() {
+        let universe = Universe::parse("#.#\n...\n#..").unwrap();
+        // Column 1 and row 1 are empty
+        assert_eq!(
+            universe.expanded("3x5".parse().unwrap()),
+            Ok(vec![(0, 0), (4, 0), (0, 6)])
+        );
+        assert_eq!(
+            universe.expanded(Expansion::uniform(0)),
+            Ok(vec![(0, 0), (1, 0), (0, 1)])
+        );
+        assert!("2x".parse::<Expansion>().is_err());
+        assert!("-1".parse::<Expansion>().is_err());
+    }
+
+    #[test]
+    fn matches_pairwise() {
+        let universe = Universe::parse(EXAMPLE).unwrap();
+        for factor in [2, 1_000_000] {
+            let expansion = Expansion::uniform(factor);
+            assert_eq!(
+                universe.pairwise_total(expansion, &Pool::new(4)),
+                universe.total_distance(expansion)
+            );
+        }
+    }
+
+    #[test]
+    fn huge_factors() {
+        let universe = Universe::parse(EXAMPLE).unwrap();
+        // The map itself still fits, but the total doesn't
+        let huge = Expansion::uniform(1_000_000_000_000_000_000);
+        assert!(universe.expanded(huge).is_ok());
+        assert!(universe.total_distance(huge).is_err());
+        assert!(universe.pairwise_total(huge, &Pool::new(2)).is_err());
+
+        let max = Expansion::uniform(u64::MAX);
+        assert_eq!(
+            universe.expanded(max),
+            Err(format!(
+                "Expansion {0}x{0} is too big for this map",
+                u64::MAX
+            ))
+        );
+        assert!(universe.distance(max, 0, 1).is_err());
+
+        // Just under the limit, a single distance still comes out right
+        let wide = Expansion {
+            columns: u64::MAX / 4,
+            rows: 1,
+        };
+        assert_eq!(universe.distance(wide, 2, 8), Ok(u64::MAX / 4 + 10));
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{too_big, Expansion, Universe};
+use common::pool::Pool;
+
+/// Biggest expanded map `draw_path` will draw, in tiles.
+pub const MAX_DRAW: u64 = 1 << 20;
+
+/// A galaxy's closest and farthest other galaxy, as `(index, distance)`.
+/// Ties go to the lower index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Neighbours {
+    pub nearest: (usize, u64),
+    pub farthest: (usize, u64),
+}
+
+/// Reads a pair of galaxies as `A,B`, numbered from 1 like the puzzle
+/// does, and gives back their indices.
+pub fn parse_pair(s: &'_ str) -> Result<(usize, usize), String> {
+    let number = |n: &str| match n.trim().parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n - 1),
+        _ => Err(format!("Invalid galaxy number '{n}'")),
+    };
+    let (a, b) = s
+        .split_once(',')
+        .ok_or(format!("Expected a galaxy pair like 5,9, found '{s}'"))?;
+    Ok((number(a)?, number(b)?))
+}
+
+fn manhattan(a: (u64, u64), b: (u64, u64)) -> u64 {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+impl Universe {
+    fn check_galaxy(&self, i: usize) -> Result<(), String> {
+        if i < self.galaxies.len() {
+            Ok(())
+        } else {
+            Err(format!(
+                "No galaxy {}, there are only {}",
+                i + 1,
+                self.galaxies.len()
+            ))
+        }
+    }
+
+    /// Shortest distance between galaxies `i` and `j`.
+    pub fn distance(&self, expansion: Expansion, i: usize, j: usize) -> Result<u64, String> {
+        self.check_galaxy(i)?;
+        self.check_galaxy(j)?;
+        let g = self.expanded(expansion)?;
+        Ok(manhattan(g[i], g[j]))
+    }
+
+    /// Every galaxy's neighbours, in galaxy order. Empty if there aren't at
+    /// least two galaxies.
+    pub fn neighbours(&self, expansion: Expansion, pool: &Pool) -> Result<Vec<Neighbours>, String> {
+        let g = self.expanded(expansion)?;
+        if g.len() < 2 {
+            return Ok(Vec::new());
+        }
+        let idx: Vec<usize> = (0..g.len()).collect();
+        Ok(pool.map(&idx, |i| {
+            let mut others = (0..g.len())
+                .filter(|j| j != i)
+                .map(|j| (j, manhattan(g[*i], g[j])));
+            let first = others.next().unwrap();
+            let mut res = Neighbours {
+                nearest: first,
+                farthest: first,
+            };
+            for (j, d) in others {
+                if d < res.nearest.1 {
+                    res.nearest = (j, d);
+                }
+                if d > res.farthest.1 {
+                    res.farthest = (j, d);
+                }
+            }
+            res
+        }))
+    }
+
+    /// One shortest path from galaxy `i` to galaxy `j` over the expanded
+    /// map, including both ends. It goes across first, then up or down.
+    pub fn path(
+        &self,
+        expansion: Expansion,
+        i: usize,
+        j: usize,
+    ) -> Result<Vec<(u64, u64)>, String> {
+        self.check_galaxy(i)?;
+        self.check_galaxy(j)?;
+        let g = self.expanded(expansion)?;
+        let (from, to) = (g[i], g[j]);
+
+        let mut res = vec![from];
+        let (mut x, mut y) = from;
+        while x != to.0 {
+            x = if x < to.0 { x + 1 } else { x - 1 };
+            res.push((x, y));
+        }
+        while y != to.1 {
+            y = if y < to.1 { y + 1 } else { y - 1 };
+            res.push((x, y));
+        }
+        Ok(res)
+    }
+
+    /// Size of the map once expanded, as `(width, height)`. An error if
+    /// either side doesn't fit in `u64`.
+    pub fn expanded_size(&self, expansion: Expansion) -> Result<(u64, u64), String> {
+        let grow = |len: u64, empty: u64, factor: u64| {
+            empty
+                .checked_mul(factor)
+                .and_then(|e| e.checked_add(len - empty))
+                .ok_or_else(|| too_big(expansion))
+        };
+        Ok((
+            grow(
+                self.width,
+                self.empty_columns.len() as u64,
+                expansion.columns,
+            )?,
+            grow(self.height, self.empty_rows.len() as u64, expansion.rows)?,
+        ))
+    }
+
+    /// The expanded map with galaxies as `#` and the path from `path`
+    /// between them as `*`. Refuses maps bigger than `MAX_DRAW` tiles.
+    pub fn draw_path(&self, expansion: Expansion, i: usize, j: usize) -> Result<String, String> {
+        let (width, height) = self.expanded_size(expansion)?;
+        if width.saturating_mul(height) > MAX_DRAW {
+            return Err(format!("Expanded map is {width}x{height}, too big to draw"));
+        }
+
+        let (width, height) = (width as usize, height as usize);
+        let mut map = vec![vec!['.'; width]; height];
+        for (x, y) in self.path(expansion, i, j)? {
+            map[y as usize][x as usize] = '*';
+        }
+        for (x, y) in self.expanded(expansion)? {
+            map[y as usize][x as usize] = '#';
+        }
+
+        let mut out = String::with_capacity((width + 1) * height);
+        for row in map {
+            out.extend(row);
+            out.push('\n');
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[test]
+    fn distances() {
+        let universe = Universe::parse(EXAMPLE).unwrap();
+        let twice = Expansion::uniform(2);
+        // Galaxies 5 and 9, 1 and 7, 3 and 6, 8 and 9 in the puzzle's
+        // numbering
+        assert_eq!(universe.distance(twice, 4, 8), Ok(9));
+        assert_eq!(universe.distance(twice, 0, 6), Ok(15));
+        assert_eq!(universe.distance(twice, 2, 5), Ok(17));
+        assert_eq!(universe.distance(twice, 7, 8), Ok(5));
+        assert!(universe.distance(twice, 0, 9).is_err());
+        assert_eq!(parse_pair("5,9"), Ok((4, 8)));
+        assert!(parse_pair("0,9").is_err());
+        assert!(parse_pair("5").is_err());
+
+        let path = universe.path(twice, 4, 8).unwrap();
+        assert_eq!(path.len(), 10);
+        assert_eq!(path[0], (1, 6));
+        assert_eq!(path[9], (5, 11));
+    }
+
+    #[test]
+    fn missing_galaxy() {
+        let universe = Universe::parse(EXAMPLE).unwrap();
+        let twice = Expansion::uniform(2);
+        let err = "No galaxy 10, there are only 9".to_string();
+        assert_eq!(universe.distance(twice, 9, 0), Err(err.clone()));
+        assert_eq!(universe.path(twice, 0, 9), Err(err));
+    }
+
+    #[test]
+    fn neighbours() {
+        let universe = Universe::parse("#...#\n.....\n#....").unwrap();
+        let n = universe
+            .neighbours(Expansion::uniform(1), &Pool::new(2))
+            .unwrap();
+        assert_eq!(
+            n[0],
+            Neighbours {
+                nearest: (2, 2),
+                farthest: (1, 4)
+            }
+        );
+        assert_eq!(n[1].farthest, (2, 6));
+        assert!(Universe::parse("#")
+            .unwrap()
+            .neighbours(Expansion::uniform(2), &Pool::new(1))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn drawing() {
+        let universe = Universe::parse("#..\n...\n..#").unwrap();
+        let map = universe.draw_path(Expansion::uniform(2), 0, 1).unwrap();
+        assert_eq!(map, "#***\n...*\n...*\n...#\n");
+        assert!(universe
+            .draw_path(Expansion::uniform(1_000_000), 0, 1)
+            .is_err());
+    }
+}
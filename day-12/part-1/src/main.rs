@@ -1,124 +1,84 @@
-use std::collections::HashMap;
-use std::env::args;
+use springs::{parse_factors, parse_separator, Record, Report, Table, XorShift, UNKNOWN};
+use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 fn main() {
-    let args: Vec<String> = args().collect();
+    let mut path = String::new();
+    let mut factors: Vec<usize> = vec![1];
+    let mut separator = UNKNOWN;
+    let mut show = 0;
+    let mut samples = 0;
+    let mut analyse = false;
+    let mut seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--unfold" => factors = parse_factors(&args.next().unwrap()).unwrap(),
+            "--separator" => separator = parse_separator(&args.next().unwrap()).unwrap(),
+            "--show" => show = args.next().unwrap().parse().unwrap(),
+            "--sample" => samples = args.next().unwrap().parse().unwrap(),
+            "--seed" => seed = args.next().unwrap().parse().unwrap(),
+            "--analyse" => analyse = true,
+            _ => path = arg,
+        }
+    }
 
-    let f = File::open(&args[1]).unwrap();
+    let f = File::open(&path).unwrap();
     let input = BufReader::new(f);
 
-    let mut total = 0;
+    let mut rng = XorShift::new(seed);
+    let mut report = Report::default();
+
+    // One total per unfold factor, so a series shows how the counts grow
+    let mut totals: Vec<u128> = vec![0; factors.len()];
     for line in input.lines() {
         let line = line.unwrap();
-        let mut r = Record::parse(&line);
-        let t = r.variants();
-        println!("\n{}: {:?} | {}", line, r.row, t);
-        total += t;
-    }
-
-    println!("Answer: {total}");
-}
-
-struct Record {
-    row: Row,
-    groups: Vec<u8>,
-    memo: HashMap<Row, u32>,
-}
-
-impl Record {
-    fn parse(line: &'_ str) -> Self {
-        let mut row: Row = Vec::new();
-        let mut groups: Vec<u8> = Vec::new();
-        let mut line = line.split_whitespace();
-
-        for c in line.next().unwrap().chars() {
-            row.push(match c {
-                '.' => Spring::Operational,
-                '#' => Spring::Damaged,
-                '?' => Spring::Unknown,
-                _ => unreachable!(),
-            })
-        }
-
-        for n in line.next().unwrap().split(',') {
-            groups.push(n.parse().unwrap());
-        }
-
-        Self {
-            row,
-            groups,
-            memo: HashMap::new(),
-        }
-    }
-
-    fn variants(&mut self) -> u32 {
-        let row = self.row.to_owned();
-        self.gen(&row)
-    }
-
-    fn gen(&mut self, row: &Row) -> u32 {
-        if let Some(total) = self.memo.get(row) {
-            return *total;
+        let record = Record::parse(&line).unwrap();
+        let mut counts: Vec<String> = Vec::new();
+        let mut unfolded: Vec<Record> = Vec::new();
+        for (k, total) in factors.iter().zip(totals.iter_mut()) {
+            let r = record.unfold(*k, separator);
+            let t = r.count().unwrap();
+            *total = total.checked_add(t).unwrap();
+            counts.push(t.to_string());
+            unfolded.push(r);
         }
+        println!("{line}: {}", counts.join(" "));
 
-        let mut res = 0;
-        let mut new_row: Row = Vec::new();
-        let mut branch_idx = 0;
-        let mut branch: bool = false;
-        for (i, s) in row.iter().enumerate() {
-            if *s == Spring::Unknown && !branch {
-                branch = true;
-                branch_idx = i;
+        if analyse {
+            for r in unfolded.iter() {
+                println!("    {}", report.add(r).unwrap());
             }
-            new_row.push(*s);
-        }
-
-        if !branch {
-            return self.check(row);
         }
 
-        new_row[branch_idx] = Spring::Operational;
-        res += self.gen(&new_row);
-        new_row[branch_idx] = Spring::Damaged;
-        res += self.gen(&new_row);
-
-        res
-    }
-
-    fn check(&self, row: &Row) -> u32 {
-        let mut groups: Vec<u8> = Vec::new();
-        let mut total: u8 = 0;
-        for s in row.iter() {
-            match s {
-                Spring::Damaged => total += 1,
-                Spring::Operational => {
-                    if total != 0 {
-                        groups.push(total);
-                        total = 0;
+        if show > 0 || samples > 0 {
+            for r in unfolded.iter() {
+                let table = Table::new(r).unwrap();
+                for a in table.iter().take(show) {
+                    println!("    {a}");
+                }
+                for _ in 0..samples {
+                    if let Some(a) = table.sample(&mut rng) {
+                        println!("  ~ {a}");
                     }
                 }
-                Spring::Unknown => unreachable!(),
             }
         }
-        if total != 0 {
-            groups.push(total);
-        }
-
-        if groups == self.groups {
-            return 1;
-        }
-        0
     }
-}
 
-type Row = Vec<Spring>;
+    if analyse {
+        println!("{report}");
+    }
 
-#[repr(u8)]
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
-enum Spring {
-    Operational,
-    Damaged,
-    Unknown,
+    if let [total] = totals[..] {
+        println!("Answer: {total}");
+    } else {
+        for (k, total) in factors.iter().zip(totals.iter()) {
+            println!("Unfolded x{k}: {total}");
+        }
+    }
 }
@@ -1,107 +1,84 @@
-use std::collections::HashMap;
-use std::env::args;
+use springs::{parse_factors, parse_separator, Record, Report, Table, XorShift, UNKNOWN};
+use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::usize;
-
-const OPERATIONAL: char = '.';
-const DAMAGED: char = '#';
-const UNKNOWN: char = '?';
+use std::time::{SystemTime, UNIX_EPOCH};
 
 fn main() {
-    let args: Vec<String> = args().collect();
+    let mut path = String::new();
+    let mut factors: Vec<usize> = vec![5];
+    let mut separator = UNKNOWN;
+    let mut show = 0;
+    let mut samples = 0;
+    let mut analyse = false;
+    let mut seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--unfold" => factors = parse_factors(&args.next().unwrap()).unwrap(),
+            "--separator" => separator = parse_separator(&args.next().unwrap()).unwrap(),
+            "--show" => show = args.next().unwrap().parse().unwrap(),
+            "--sample" => samples = args.next().unwrap().parse().unwrap(),
+            "--seed" => seed = args.next().unwrap().parse().unwrap(),
+            "--analyse" => analyse = true,
+            _ => path = arg,
+        }
+    }
 
-    let f = File::open(&args[1]).unwrap();
+    let f = File::open(&path).unwrap();
     let input = BufReader::new(f);
 
-    let mut total = 0;
+    let mut rng = XorShift::new(seed);
+    let mut report = Report::default();
+
+    // One total per unfold factor, so a series shows how the counts grow
+    let mut totals: Vec<u128> = vec![0; factors.len()];
     for line in input.lines() {
         let line = line.unwrap();
-        let t = calc(&line);
-        println!("{line}: {t}");
-        total += t;
-    }
-
-    println!("Answer: {total}");
-}
-
-fn calc_with_cache(
-    springs: &'_ str,
-    groups: &'_ [u8],
-    cache: &mut HashMap<(String, Vec<u8>), u64>,
-) -> u64 {
-    if let Some(hit) = cache.get(&(springs.into(), groups.into())) {
-        return *hit;
-    }
-
-    if groups.is_empty() {
-        if springs.contains('#') {
-            return 0;
-        } else {
-            return 1;
+        let record = Record::parse(&line).unwrap();
+        let mut counts: Vec<String> = Vec::new();
+        let mut unfolded: Vec<Record> = Vec::new();
+        for (k, total) in factors.iter().zip(totals.iter_mut()) {
+            let r = record.unfold(*k, separator);
+            let t = r.count().unwrap();
+            *total = total.checked_add(t).unwrap();
+            counts.push(t.to_string());
+            unfolded.push(r);
         }
-    }
-    if springs.len() < groups[0] as usize {
-        return 0;
-    }
+        println!("{line}: {}", counts.join(" "));
 
-    let mut res = 0;
-    let this_spring = springs.chars().nth(0).unwrap();
-    if this_spring == OPERATIONAL || this_spring == UNKNOWN {
-        res += calc_with_cache(&springs[1..], groups, cache);
-    }
-    if this_spring == DAMAGED || this_spring == UNKNOWN {
-        res += group_check(springs, groups, cache);
-    }
-
-    cache.insert((springs.into(), groups.into()), res);
-    res
-}
-
-fn group_check(
-    springs: &'_ str,
-    groups: &'_ [u8],
-    cache: &mut HashMap<(String, Vec<u8>), u64>,
-) -> u64 {
-    let group_size = groups[0] as usize;
-    let this_group = &springs[0..group_size];
-
-    if this_group.contains(OPERATIONAL) {
-        return 0;
-    }
+        if analyse {
+            for r in unfolded.iter() {
+                println!("    {}", report.add(r).unwrap());
+            }
+        }
 
-    if group_size == springs.len() {
-        if groups.len() == 1 {
-            return 1;
-        } else {
-            return 0;
+        if show > 0 || samples > 0 {
+            for r in unfolded.iter() {
+                let table = Table::new(r).unwrap();
+                for a in table.iter().take(show) {
+                    println!("    {a}");
+                }
+                for _ in 0..samples {
+                    if let Some(a) = table.sample(&mut rng) {
+                        println!("  ~ {a}");
+                    }
+                }
+            }
         }
     }
 
-    let next_spring = springs.chars().nth(group_size).unwrap();
-    if next_spring == OPERATIONAL || next_spring == UNKNOWN {
-        return calc_with_cache(&springs[(group_size + 1)..], &groups[1..], cache);
+    if analyse {
+        println!("{report}");
     }
 
-    0
-}
-
-fn calc(line: &'_ str) -> u64 {
-    let mut line = line.split_whitespace();
-    let springs_temp = line.next().unwrap();
-    let mut groups_temp: Vec<u8> = Vec::new();
-    for n in line.next().unwrap().split(',') {
-        groups_temp.push(n.parse().unwrap());
-    }
-    let mut springs = String::new();
-    let mut groups: Vec<u8> = Vec::new();
-    for _ in 0..5 {
-        springs.push_str(springs_temp);
-        springs.push('?');
-        groups.append(&mut groups_temp.clone());
+    if let [total] = totals[..] {
+        println!("Answer: {total}");
+    } else {
+        for (k, total) in factors.iter().zip(totals.iter()) {
+            println!("Unfolded x{k}: {total}");
+        }
     }
-    springs.pop();
-
-    let mut cache: HashMap<(String, Vec<u8>), u64> = HashMap::new();
-    calc_with_cache(&springs, &groups, &mut cache)
 }
@@ -0,0 +1,216 @@
+use crate::{Record, Table, DAMAGED, OPERATIONAL, OVERFLOW, UNKNOWN};
+use std::fmt;
+
+/// What every arrangement of a record has in common, like a nonogram line
+/// solver would work out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    pub count: u128,
+    /// False when the groups need more springs than the row has, before
+    /// even looking at which springs are known.
+    pub fits: bool,
+    /// Unknown springs that are damaged in every arrangement.
+    pub forced_damaged: Vec<usize>,
+    /// Unknown springs that are operational in every arrangement.
+    pub forced_operational: Vec<usize>,
+    /// The row with the forced springs filled in.
+    pub resolved: Vec<u8>,
+}
+
+impl Analysis {
+    /// Only one arrangement, so every spring is known.
+    pub fn is_determined(&self) -> bool {
+        self.count == 1
+    }
+}
+
+/// Tally of analysed records, shown as a one line summary.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub records: usize,
+    pub impossible: usize,
+    pub too_long: usize,
+    pub determined: usize,
+}
+
+impl Report {
+    /// Analyses one record, adds it to the tally, and describes what's
+    /// forced in it.
+    pub fn add(&mut self, record: &Record) -> Result<String, String> {
+        let analysis = record.analyse()?;
+        self.records += 1;
+        if !analysis.fits {
+            self.too_long += 1;
+            self.impossible += 1;
+            return Ok(format!(
+                "groups need {} springs, row has {}",
+                record.min_len(),
+                record.springs.len()
+            ));
+        }
+        if analysis.count == 0 {
+            self.impossible += 1;
+            return Ok("no arrangements".to_string());
+        }
+        if analysis.is_determined() {
+            self.determined += 1;
+        }
+        Ok(format!(
+            "{} | damaged {:?}, operational {:?}",
+            String::from_utf8_lossy(&analysis.resolved),
+            analysis.forced_damaged,
+            analysis.forced_operational
+        ))
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Records: {}, impossible: {} ({} too long for their row), determined: {}",
+            self.records, self.impossible, self.too_long, self.determined
+        )
+    }
+}
+
+impl Record {
+    /// Counts, for every spring, the arrangements where it's damaged.
+    ///
+    /// `before[g][i]` is the number of ways to fill `springs[..i]` with the
+    /// first `g` groups, leaving `i` free for the next group. Multiplied by
+    /// the table's count for the groups after, that gives the number of
+    /// arrangements with group `g` starting at `i`, which is added over
+    /// the springs it covers.
+    pub fn analyse(&self) -> Result<Analysis, String> {
+        let n = self.springs.len();
+        let table = Table::new(self)?;
+        let count = table.total();
+        let mut res = Analysis {
+            count,
+            fits: self.min_len() <= n,
+            forced_damaged: Vec::new(),
+            forced_operational: Vec::new(),
+            resolved: self.springs.clone(),
+        };
+        if count == 0 {
+            return Ok(res);
+        }
+
+        let groups = &self.groups;
+        let mut before = vec![vec![0u128; n + 1]; groups.len() + 1];
+        before[0][0] = 1;
+        // Differences of the damaged counts, summed up afterwards. Each sum
+        // is at most `count`, so wrapping along the way comes out right.
+        let mut damaged = vec![0u128; n + 1];
+        for i in 0..n {
+            for g in 0..=groups.len() {
+                let ways = before[g][i];
+                if ways == 0 {
+                    continue;
+                }
+                if self.springs[i] != DAMAGED {
+                    before[g][i + 1] = before[g][i + 1].checked_add(ways).ok_or(OVERFLOW)?;
+                }
+                if g < groups.len() && self.fits(&table.runs, i, groups[g]) {
+                    let size = groups[g];
+                    let end = (i + size + 1).min(n);
+                    before[g + 1][end] = before[g + 1][end].checked_add(ways).ok_or(OVERFLOW)?;
+
+                    let through = ways.checked_mul(table.ways[g + 1][end]).ok_or(OVERFLOW)?;
+                    damaged[i] = damaged[i].wrapping_add(through);
+                    damaged[i + size] = damaged[i + size].wrapping_sub(through);
+                }
+            }
+        }
+
+        let mut running: u128 = 0;
+        for (i, diff) in damaged.iter().take(n).enumerate() {
+            running = running.wrapping_add(*diff);
+            if self.springs[i] != UNKNOWN {
+                continue;
+            }
+            if running == count {
+                res.forced_damaged.push(i);
+                res.resolved[i] = DAMAGED;
+            } else if running == 0 {
+                res.forced_operational.push(i);
+                res.resolved[i] = OPERATIONAL;
+            }
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn forced_springs() {
+        let record = Record::parse("?###???????? 3,2,1").unwrap();
+        let analysis = record.analyse().unwrap();
+        assert_eq!(analysis.count, 10);
+        assert_eq!(analysis.forced_operational, vec![0, 4]);
+        assert!(analysis.forced_damaged.is_empty());
+        assert_eq!(analysis.resolved, b".###.???????");
+
+        let record = Record::parse("???.### 1,1,3").unwrap();
+        let analysis = record.analyse().unwrap();
+        assert!(analysis.is_determined());
+        assert_eq!(analysis.resolved, b"#.#.###");
+        assert_eq!(analysis.forced_damaged, vec![0, 2]);
+
+        // Overlap in the middle of a long group, like a nonogram
+        let record = Record::parse("?????? 4").unwrap();
+        let analysis = record.analyse().unwrap();
+        assert_eq!(analysis.forced_damaged, vec![2, 3]);
+    }
+
+    #[test]
+    fn contradictions() {
+        let analysis = Record::parse("#.# 1").unwrap().analyse().unwrap();
+        assert_eq!(analysis.count, 0);
+        assert!(analysis.fits);
+
+        let analysis = Record::parse("???? 2,2").unwrap().analyse().unwrap();
+        assert_eq!(analysis.count, 0);
+        assert!(!analysis.fits);
+    }
+
+    #[test]
+    fn report() {
+        let mut report = Report::default();
+        let mut add = |l: &str| report.add(&Record::parse(l).unwrap()).unwrap();
+        assert_eq!(
+            add("???.### 1,1,3"),
+            "#.#.### | damaged [0, 2], operational [1]"
+        );
+        assert_eq!(add("#.# 1"), "no arrangements");
+        assert_eq!(add("???? 2,2"), "groups need 5 springs, row has 4");
+        assert_eq!(
+            add("?###???????? 3,2,1"),
+            ".###.??????? | damaged [], operational [0, 4]"
+        );
+        assert_eq!(
+            report.to_string(),
+            "Records: 4, impossible: 2 (1 too long for their row), determined: 1"
+        );
+    }
+
+    #[test]
+    fn matches_enumeration() {
+        let record = Record::parse(".??..??...?##.?? 1,1,3").unwrap();
+        let analysis = record.analyse().unwrap();
+        let table = Table::new(&record).unwrap();
+        let all: Vec<String> = table.iter().collect();
+        for (i, c) in record.springs.iter().enumerate() {
+            if *c != UNKNOWN {
+                continue;
+            }
+            let damaged = all.iter().filter(|a| a.as_bytes()[i] == DAMAGED).count();
+            assert_eq!(analysis.forced_damaged.contains(&i), damaged == all.len());
+            assert_eq!(analysis.forced_operational.contains(&i), damaged == 0);
+        }
+    }
+}
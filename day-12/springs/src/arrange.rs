@@ -0,0 +1,246 @@
+use crate::{Record, DAMAGED, OPERATIONAL};
+
+/// The whole counting DP for one record, every group's row kept.
+///
+/// `ways[g][i]` is the number of ways to place groups `g..` in
+/// `springs[i..]`, so from any partial arrangement the table says how many
+/// finished ones lie down each choice. That lets arrangements be listed
+/// without ever following a dead end, and sampled without bias.
+#[derive(Debug)]
+pub struct Table<'a> {
+    record: &'a Record,
+    pub(crate) runs: Vec<usize>,
+    pub(crate) ways: Vec<Vec<u128>>,
+}
+
+impl<'a> Table<'a> {
+    pub fn new(record: &'a Record) -> Result<Self, String> {
+        let n = record.springs.len();
+        let runs = record.runs();
+        let mut ways = vec![Vec::new(); record.groups.len() + 1];
+        ways[record.groups.len()] = record.last_row();
+        for (g, &size) in record.groups.iter().enumerate().rev() {
+            let mut row = vec![0; n + 2];
+            if n > 0 {
+                record.fill_row(&runs, size, 0..=(n - 1), &ways[g + 1], &mut row)?;
+            }
+            ways[g] = row;
+        }
+        Ok(Self { record, runs, ways })
+    }
+
+    pub fn total(&self) -> u128 {
+        self.ways[0][0]
+    }
+
+    /// Every arrangement, generated as it's asked for.
+    pub fn iter(&self) -> Arrangements<'_> {
+        let mut stack = Vec::new();
+        if self.total() > 0 {
+            stack.push(Partial {
+                group: 0,
+                pos: 0,
+                row: Vec::with_capacity(self.record.springs.len()),
+            });
+        }
+        Arrangements { table: self, stack }
+    }
+
+    /// One arrangement picked uniformly at random, or `None` if there
+    /// aren't any.
+    pub fn sample(&self, rng: &mut XorShift) -> Option<String> {
+        let mut state = Partial {
+            group: 0,
+            pos: 0,
+            row: Vec::with_capacity(self.record.springs.len()),
+        };
+        let mut pick = rng.below(self.total())?;
+        loop {
+            let (operational, damaged) = self.choices(&state);
+            match (operational, damaged) {
+                (None, None) => return Some(state.into_string()),
+                (Some((next, count)), _) if pick < count => state = next,
+                (Some((_, count)), Some((next, _))) => {
+                    pick -= count;
+                    state = next;
+                }
+                (None, Some((next, _))) => state = next,
+                (Some(_), None) => unreachable!(),
+            }
+        }
+    }
+
+    /// The ways forward from a partial arrangement that lead anywhere, with
+    /// how many arrangements each one leads to. Both `None` once the row is
+    /// finished.
+    fn choices(&self, state: &Partial) -> (Choice, Choice) {
+        let springs = &self.record.springs;
+        let groups = &self.record.groups;
+        let (g, i) = (state.group, state.pos);
+        if i >= springs.len() {
+            return (None, None);
+        }
+
+        let mut operational = None;
+        if springs[i] != DAMAGED && self.ways[g][i + 1] > 0 {
+            let mut row = state.row.clone();
+            row.push(OPERATIONAL);
+            let next = Partial {
+                group: g,
+                pos: i + 1,
+                row,
+            };
+            operational = Some((next, self.ways[g][i + 1]));
+        }
+
+        let mut damaged = None;
+        if g < groups.len() && self.record.fits(&self.runs, i, groups[g]) {
+            let size = groups[g];
+            let end = (i + size + 1).min(springs.len());
+            if self.ways[g + 1][end] > 0 {
+                let mut row = state.row.clone();
+                row.resize(i + size, DAMAGED);
+                row.resize(end, OPERATIONAL);
+                let next = Partial {
+                    group: g + 1,
+                    pos: end,
+                    row,
+                };
+                damaged = Some((next, self.ways[g + 1][end]));
+            }
+        }
+        (operational, damaged)
+    }
+}
+
+/// A step to take, and how many arrangements lie beyond it.
+type Choice = Option<(Partial, u128)>;
+
+#[derive(Debug, Clone)]
+struct Partial {
+    group: usize,
+    pos: usize,
+    row: Vec<u8>,
+}
+
+impl Partial {
+    fn into_string(self) -> String {
+        String::from_utf8(self.row).unwrap()
+    }
+}
+
+/// Iterator over a record's arrangements as `#`/`.` strings, with
+/// operational springs tried before damaged ones at each step.
+pub struct Arrangements<'a> {
+    table: &'a Table<'a>,
+    stack: Vec<Partial>,
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(state) = self.stack.pop() {
+            match self.table.choices(&state) {
+                (None, None) => return Some(state.into_string()),
+                (operational, damaged) => {
+                    // Pushed in reverse so operational comes off first
+                    if let Some((next, _)) = damaged {
+                        self.stack.push(next);
+                    }
+                    if let Some((next, _)) = operational {
+                        self.stack.push(next);
+                    }
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Marsaglia's xorshift64*, plenty for picking sample arrangements.
+#[derive(Debug, Clone)]
+pub struct XorShift(u64);
+
+impl XorShift {
+    /// Any seed works, 0 is swapped for a fixed non-zero one.
+    pub fn new(seed: u64) -> Self {
+        Self(if seed == 0 {
+            0x9e37_79b9_7f4a_7c15
+        } else {
+            seed
+        })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// A number in `0..bound` with no modulo bias, `None` if `bound` is 0.
+    pub fn below(&mut self, bound: u128) -> Option<u128> {
+        if bound == 0 {
+            return None;
+        }
+        // Values past the last whole multiple of `bound` are thrown away
+        let limit = u128::MAX - (u128::MAX % bound);
+        loop {
+            let x = ((self.next_u64() as u128) << 64) | self.next_u64() as u128;
+            if x < limit {
+                return Some(x % bound);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn lists_every_arrangement() {
+        let record = Record::parse("?###???????? 3,2,1").unwrap();
+        let table = Table::new(&record).unwrap();
+        let all: Vec<String> = table.iter().collect();
+        assert_eq!(all.len() as u128, table.total());
+        assert_eq!(all[0], ".###....##.#");
+        assert_eq!(all[9], ".###.##.#...");
+
+        let record = Record::parse("???.### 1,1,3").unwrap();
+        let table = Table::new(&record).unwrap();
+        assert_eq!(table.iter().collect::<Vec<_>>(), vec!["#.#.###"]);
+
+        let record = Record::parse("#.# 1").unwrap();
+        assert_eq!(Table::new(&record).unwrap().iter().next(), None);
+    }
+
+    #[test]
+    fn lazy_on_huge_counts() {
+        let record = Record::parse("?#? 2").unwrap().unfold(100, OPERATIONAL);
+        let table = Table::new(&record).unwrap();
+        assert_eq!(table.total(), 1 << 100);
+        let first: Vec<String> = table.iter().take(3).collect();
+        assert_eq!(first.len(), 3);
+        assert!(first[0].ends_with(".##"));
+    }
+
+    #[test]
+    fn samples_evenly() {
+        let record = Record::parse(".??..??...?##. 1,1,3").unwrap();
+        let table = Table::new(&record).unwrap();
+        let mut rng = XorShift::new(7);
+        let mut seen: HashMap<String, usize> = HashMap::new();
+        for _ in 0..4000 {
+            *seen.entry(table.sample(&mut rng).unwrap()).or_default() += 1;
+        }
+        assert_eq!(seen.len(), 4);
+        assert!(seen.values().all(|n| (850..1150).contains(n)));
+        assert!(seen.keys().all(|a| table.iter().any(|b| &b == a)));
+
+        let impossible = Record::parse("## 1").unwrap();
+        assert_eq!(Table::new(&impossible).unwrap().sample(&mut rng), None);
+    }
+}
@@ -0,0 +1,327 @@
+mod analyse;
+mod arrange;
+
+pub use analyse::{Analysis, Report};
+pub use arrange::{Arrangements, Table, XorShift};
+
+use std::ops::RangeInclusive;
+
+pub const OPERATIONAL: u8 = b'.';
+pub const DAMAGED: u8 = b'#';
+pub const UNKNOWN: u8 = b'?';
+
+/// One line of the condition report: a row of springs as bytes, and the
+/// sizes of the damaged groups in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub springs: Vec<u8>,
+    pub groups: Vec<usize>,
+}
+
+impl Record {
+    pub fn parse(line: &'_ str) -> Result<Self, String> {
+        let (springs, groups) = line
+            .split_once(' ')
+            .ok_or(format!("Expected springs and groups in '{line}'"))?;
+
+        let springs = springs.as_bytes().to_vec();
+        if let Some(c) = springs
+            .iter()
+            .find(|c| ![OPERATIONAL, DAMAGED, UNKNOWN].contains(c))
+        {
+            return Err(format!("Invalid spring '{}'", *c as char));
+        }
+
+        let groups = groups
+            .trim()
+            .split(',')
+            .map(|n| match n.parse::<usize>() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => Err(format!("Invalid group size '{n}'")),
+            })
+            .collect::<Result<Vec<usize>, String>>()?;
+
+        Ok(Self { springs, groups })
+    }
+
+    /// The record repeated `times` times, with the rows joined by
+    /// `separator`.
+    pub fn unfold(&self, times: usize, separator: u8) -> Self {
+        let mut springs = Vec::with_capacity((self.springs.len() + 1) * times);
+        for i in 0..times {
+            if i > 0 {
+                springs.push(separator);
+            }
+            springs.extend_from_slice(&self.springs);
+        }
+        Self {
+            springs,
+            groups: self.groups.repeat(times),
+        }
+    }
+
+    /// Number of ways to fill in the unknown springs so the damaged groups
+    /// come out right.
+    ///
+    /// `ways[i]` is the number of ways to place groups `g..` in
+    /// `springs[i..]`. Each group's row only needs the row for the group
+    /// after it, so groups are filled in from the last one back and only
+    /// two rows are kept. A group can only start where there's room for
+    /// the groups either side of it, so each row only covers that band.
+    pub fn count(&self) -> Result<u128, String> {
+        let n = self.springs.len();
+        let runs = self.runs();
+        let (lower, upper) = match self.bands() {
+            Some(bands) => bands,
+            None => return Ok(0),
+        };
+
+        let mut next = self.last_row();
+        // Highest entry in `ways` that might be left over from an earlier
+        // group
+        let mut ways = vec![0; n + 2];
+        let mut ways_top = 0;
+        let mut next_top = n;
+        for (g, &size) in self.groups.iter().enumerate().rev() {
+            let top = upper[g];
+            ways[(top + 1)..=ways_top.max(top + 1)].fill(0);
+            self.fill_row(&runs, size, lower[g]..=top, &next, &mut ways)?;
+            std::mem::swap(&mut ways, &mut next);
+            ways_top = next_top;
+            next_top = top;
+        }
+        Ok(next[0])
+    }
+
+    /// The DP row for when every group has been placed: the rest of the
+    /// row can't have any damage. Padded to `len + 2` like the other rows.
+    pub(crate) fn last_row(&self) -> Vec<u128> {
+        let n = self.springs.len();
+        let mut row = vec![0; n + 2];
+        row[n] = 1;
+        for i in (0..n).rev() {
+            if self.springs[i] != DAMAGED {
+                row[i] = row[i + 1];
+            }
+        }
+        row
+    }
+
+    /// Fills in `ways` over `range` for a group of `size`, given `next`,
+    /// the row for the groups after it.
+    pub(crate) fn fill_row(
+        &self,
+        runs: &[usize],
+        size: usize,
+        range: RangeInclusive<usize>,
+        next: &[u128],
+        ways: &mut [u128],
+    ) -> Result<(), String> {
+        let n = self.springs.len();
+        for i in range.rev() {
+            let mut total: u128 = 0;
+            if self.springs[i] != DAMAGED {
+                total = ways[i + 1];
+            }
+            if self.fits(runs, i, size) {
+                // Skip the group and the operational spring after it
+                let skip = next[(i + size + 1).min(n)];
+                total = total.checked_add(skip).ok_or(OVERFLOW)?;
+            }
+            ways[i] = total;
+        }
+        Ok(())
+    }
+
+    /// The shortest row the groups fit in, with one operational spring
+    /// between each.
+    pub fn min_len(&self) -> usize {
+        let damaged: usize = self.groups.iter().sum();
+        damaged + self.groups.len().saturating_sub(1)
+    }
+
+    /// The first and last place each group could start, leaving room for
+    /// the groups before and after it. `None` if the groups can't fit in
+    /// the row at all.
+    fn bands(&self) -> Option<(Vec<usize>, Vec<usize>)> {
+        let n = self.springs.len();
+        if self.min_len() > n {
+            return None;
+        }
+        let mut lower = Vec::with_capacity(self.groups.len());
+        let mut start = 0;
+        for size in self.groups.iter() {
+            lower.push(start);
+            start += size + 1;
+        }
+
+        let mut upper = vec![0; self.groups.len()];
+        let mut end = n + 1;
+        for (g, size) in self.groups.iter().enumerate().rev() {
+            end -= size + 1;
+            upper[g] = end;
+        }
+        Some((lower, upper))
+    }
+
+    /// Length of the stretch of possibly damaged springs starting at each
+    /// position.
+    pub(crate) fn runs(&self) -> Vec<usize> {
+        let mut runs = vec![0; self.springs.len() + 1];
+        for i in (0..self.springs.len()).rev() {
+            if self.springs[i] != OPERATIONAL {
+                runs[i] = runs[i + 1] + 1;
+            }
+        }
+        runs
+    }
+
+    /// Whether a group of `size` damaged springs can start at `i`, with no
+    /// damaged spring right after it.
+    pub(crate) fn fits(&self, runs: &[usize], i: usize, size: usize) -> bool {
+        runs[i] >= size && self.springs.get(i + size) != Some(&DAMAGED)
+    }
+}
+
+const OVERFLOW: &str = "Arrangement count overflowed u128";
+
+pub fn parse_separator(s: &'_ str) -> Result<u8, String> {
+    match s.as_bytes() {
+        [c] if [OPERATIONAL, DAMAGED, UNKNOWN].contains(c) => Ok(*c),
+        _ => Err(format!("Invalid separator '{s}', expected one of .#?")),
+    }
+}
+
+/// Unfold factors as a single number `5`, a list `1,2,5`, or an inclusive
+/// range `1-8`.
+pub fn parse_factors(s: &'_ str) -> Result<Vec<usize>, String> {
+    let number = |n: &str| {
+        n.trim()
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid unfold factor '{n}'"))
+    };
+    if let Some((from, to)) = s.split_once('-') {
+        let (from, to) = (number(from)?, number(to)?);
+        if from > to {
+            return Err(format!("Empty unfold range '{s}'"));
+        }
+        return Ok((from..=to).collect());
+    }
+    s.split(',').map(number).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 6] = [
+        "???.### 1,1,3",
+        ".??..??...?##. 1,1,3",
+        "?#?#?#?#?#?#?#? 1,3,1,6",
+        "????.#...#... 4,1,1",
+        "????.######..#####. 1,6,5",
+        "?###???????? 3,2,1",
+    ];
+
+    fn records() -> Vec<Record> {
+        EXAMPLE.iter().map(|l| Record::parse(l).unwrap()).collect()
+    }
+
+    #[test]
+    fn counts() {
+        let counts: Vec<u128> = records().iter().map(|r| r.count().unwrap()).collect();
+        assert_eq!(counts, vec![1, 4, 1, 1, 4, 10]);
+    }
+
+    #[test]
+    fn unfolded_counts() {
+        let counts: Vec<u128> = records()
+            .iter()
+            .map(|r| r.unfold(5, UNKNOWN).count().unwrap())
+            .collect();
+        assert_eq!(counts, vec![1, 16384, 1, 16, 2500, 506250]);
+    }
+
+    #[test]
+    fn unfold_options() {
+        let record = Record::parse("??? 1").unwrap();
+        let joined = record.unfold(2, OPERATIONAL);
+        assert_eq!(joined.springs, b"???.???");
+        assert_eq!(joined.count(), Ok(11));
+        assert_eq!(record.unfold(1, UNKNOWN), record);
+
+        // Past u64, and then past u128
+        let pair = Record::parse("?#? 2").unwrap();
+        assert_eq!(pair.unfold(100, OPERATIONAL).count(), Ok(1 << 100));
+        assert!(pair.unfold(128, OPERATIONAL).count().is_err());
+
+        assert_eq!(parse_factors("5"), Ok(vec![5]));
+        assert_eq!(parse_factors("1,2,5"), Ok(vec![1, 2, 5]));
+        assert_eq!(parse_factors("2-4"), Ok(vec![2, 3, 4]));
+        assert!(parse_factors("4-2").is_err());
+        assert_eq!(parse_separator("."), Ok(OPERATIONAL));
+        assert!(parse_separator("x").is_err());
+    }
+
+    /// Tries every way of filling in the unknowns.
+    fn brute_force(record: &Record) -> u128 {
+        let unknown: Vec<usize> = (0..record.springs.len())
+            .filter(|i| record.springs[*i] == UNKNOWN)
+            .collect();
+        let mut total = 0;
+        for mask in 0..(1u32 << unknown.len()) {
+            let mut row = record.springs.clone();
+            for (bit, i) in unknown.iter().enumerate() {
+                row[*i] = if mask & (1 << bit) != 0 {
+                    DAMAGED
+                } else {
+                    OPERATIONAL
+                };
+            }
+            let groups: Vec<usize> = row
+                .split(|c| *c == OPERATIONAL)
+                .filter(|g| !g.is_empty())
+                .map(|g| g.len())
+                .collect();
+            if groups == record.groups {
+                total += 1;
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let mut rng = XorShift::new(0x2545_f491);
+        let mut next = |n: u128| rng.below(n).unwrap();
+        for _ in 0..500 {
+            let len = 1 + next(12) as usize;
+            let springs: Vec<u8> = (0..len)
+                .map(|_| [OPERATIONAL, DAMAGED, UNKNOWN, UNKNOWN][next(4) as usize])
+                .collect();
+            let groups: Vec<usize> = (0..=next(4)).map(|_| 1 + next(4) as usize).collect();
+            let record = Record { springs, groups };
+            assert_eq!(record.count(), Ok(brute_force(&record)), "{record:?}");
+        }
+    }
+
+    #[test]
+    fn edge_cases() {
+        let count = |l: &str| Record::parse(l).unwrap().count().unwrap();
+        assert_eq!(count("### 3"), 1);
+        assert_eq!(count("#### 3"), 0);
+        assert_eq!(count("?? 3"), 0);
+        assert_eq!(count("?#? 1"), 1);
+        assert_eq!(count("??? 1,1"), 1);
+
+        // Long rows stay linear in memory
+        let long = Record::parse("??? 1").unwrap().unfold(10_000, UNKNOWN);
+        assert_eq!(long.springs.len(), 39_999);
+        let sparse = Record::parse("#.# 1,1").unwrap();
+        assert_eq!(sparse.unfold(10_000, UNKNOWN).count(), Ok(1));
+
+        assert!(Record::parse("??x 1").is_err());
+        assert!(Record::parse("??? 1,0").is_err());
+        assert!(Record::parse("???").is_err());
+    }
+}
@@ -0,0 +1,271 @@
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// How the boat turns button time into speed.
+/// Every ms the button is held adds `acceleration` mm/ms of speed, and once
+/// released the boat sits for `delay` ms before it starts moving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Physics {
+    pub acceleration: u64,
+    pub delay: u64,
+}
+
+impl Default for Physics {
+    fn default() -> Self {
+        Self {
+            acceleration: 1,
+            delay: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Race {
+    pub time: u64,
+    pub record: u64,
+    pub physics: Physics,
+}
+
+impl Race {
+    pub fn new(time: u64, record: u64) -> Self {
+        Self {
+            time,
+            record,
+            physics: Physics::default(),
+        }
+    }
+
+    pub fn with_physics(self, physics: Physics) -> Self {
+        Self { physics, ..self }
+    }
+
+    /// Distance travelled when holding the button for `time_held` ms.
+    /// Saturates at `u64::MAX` rather than overflowing.
+    pub fn distance(&self, time_held: u64) -> u64 {
+        let moving = self.moving_time().saturating_sub(time_held) as u128;
+        let speed = self.physics.acceleration as u128 * time_held as u128;
+        u64::try_from(speed * moving).unwrap_or(u64::MAX)
+    }
+
+    pub fn time_wins(&self, time_held: u64) -> bool {
+        self.distance(time_held) > self.record
+    }
+
+    /// The distance curve is a parabola symmetric around the middle of the
+    /// moving window, so the floor of the midpoint is always a best hold.
+    pub fn optimal_hold(&self) -> u64 {
+        self.moving_time() / 2
+    }
+
+    pub fn max_distance(&self) -> u64 {
+        self.distance(self.optimal_hold())
+    }
+
+    /// Shortest and longest winning hold times, or `None` if the record
+    /// can't be beaten.
+    pub fn winning_range(&self) -> Option<RangeInclusive<u64>> {
+        let vertex = self.optimal_hold();
+        if !self.time_wins(vertex) {
+            return None;
+        }
+
+        // Distance is strictly increasing on [0, vertex], so binary search
+        // for the first winning hold and mirror it for the last one.
+        let (mut lo, mut hi) = (0, vertex);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.time_wins(mid) {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        Some(lo..=self.moving_time() - lo)
+    }
+
+    pub fn err_margin(&self) -> u64 {
+        match self.winning_range() {
+            Some(range) => range.end() - range.start() + 1,
+            None => 0,
+        }
+    }
+
+    /// Every possible hold time paired with the distance it reaches.
+    pub fn distance_curve(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        (0..=self.time).map(|held| (held, self.distance(held)))
+    }
+
+    fn moving_time(&self) -> u64 {
+        self.time.saturating_sub(self.physics.delay)
+    }
+}
+
+/// How the columns of a race sheet are read.
+/// `Spaced` treats every column as its own race, `Joined` ignores the
+/// spacing and reads each row as one long number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kerning {
+    Spaced,
+    Joined,
+}
+
+impl FromStr for Kerning {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "spaced" => Ok(Self::Spaced),
+            "joined" => Ok(Self::Joined),
+            _ => Err(format!(
+                "Invalid kerning '{s}', expected 'spaced' or 'joined'"
+            )),
+        }
+    }
+}
+
+/// Reads the Time and Distance rows. Blank lines are skipped, but errors
+/// give the line's real number in the sheet.
+pub fn parse_sheet(sheet: &'_ str, kerning: Kerning) -> Result<Vec<Race>, String> {
+    let mut lines = sheet
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l))
+        .filter(|(_, l)| !l.trim().is_empty());
+    let (time_line, times) = parse_row(lines.next(), "Time")?;
+    let (record_line, records) = parse_row(lines.next(), "Distance")?;
+    if let Some((line_no, _)) = lines.next() {
+        return Err(format!("line {line_no}: unexpected row after Distance"));
+    }
+
+    if times.len() != records.len() {
+        return Err(format!(
+            "line {record_line}: Distance row has {} columns but Time row has {}",
+            records.len(),
+            times.len()
+        ));
+    }
+
+    match kerning {
+        Kerning::Spaced => {
+            let mut res = Vec::new();
+            for (t, r) in times.iter().zip(records.iter()) {
+                res.push(Race::new(
+                    parse_num(t, time_line)?,
+                    parse_num(r, record_line)?,
+                ));
+            }
+            Ok(res)
+        }
+        Kerning::Joined => {
+            let time = parse_num(&times.concat(), time_line)?;
+            let record = parse_num(&records.concat(), record_line)?;
+            Ok(vec![Race::new(time, record)])
+        }
+    }
+}
+
+fn parse_row<'a>(
+    line: Option<(usize, &'a str)>,
+    label: &'_ str,
+) -> Result<(usize, Vec<&'a str>), String> {
+    let (line_no, line) = line.ok_or(format!("missing {label} row"))?;
+    let (name, cols) = line
+        .split_once(':')
+        .ok_or(format!("line {line_no}: expected '{label}:'"))?;
+    if name.trim() != label {
+        return Err(format!(
+            "line {line_no}: expected '{label}:', found '{name}:'"
+        ));
+    }
+    Ok((line_no, cols.split_whitespace().collect()))
+}
+
+fn parse_num(col: &'_ str, line_no: usize) -> Result<u64, String> {
+    col.parse()
+        .map_err(|_| format!("line {line_no}: invalid number '{col}'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn example_margins() {
+        assert_eq!(Race::new(7, 9).err_margin(), 4);
+        assert_eq!(Race::new(15, 40).err_margin(), 8);
+        assert_eq!(Race::new(30, 200).err_margin(), 9);
+        assert_eq!(Race::new(71530, 940200).err_margin(), 71503);
+    }
+
+    #[test]
+    fn race_queries() {
+        let race = Race::new(7, 9);
+        assert_eq!(race.winning_range(), Some(2..=5));
+        assert_eq!(race.optimal_hold(), 3);
+        assert_eq!(race.max_distance(), 12);
+
+        let curve: Vec<u64> = race.distance_curve().map(|(_, d)| d).collect();
+        assert_eq!(curve, vec![0, 6, 10, 12, 12, 10, 6, 0]);
+
+        assert_eq!(Race::new(7, 12).winning_range(), None);
+        assert_eq!(Race::new(0, 0).err_margin(), 0);
+    }
+
+    #[test]
+    fn sheet_parsing() {
+        let sheet = "Time:      7  15   30\nDistance:  9  40  200\n";
+        let races = parse_sheet(sheet, Kerning::Spaced).unwrap();
+        assert_eq!(
+            races,
+            vec![Race::new(7, 9), Race::new(15, 40), Race::new(30, 200)]
+        );
+
+        let races = parse_sheet(sheet, Kerning::Joined).unwrap();
+        assert_eq!(races, vec![Race::new(71530, 940200)]);
+
+        let bad = "Time:      7  15   30\nDistance:  9  40\n";
+        let err = parse_sheet(bad, Kerning::Spaced).unwrap_err();
+        assert!(err.starts_with("line 2:"));
+
+        // Blank lines still count towards the line number
+        let bad = "\nTime:      7  15   30\n\nDistance:  9  40\n";
+        let err = parse_sheet(bad, Kerning::Spaced).unwrap_err();
+        assert_eq!(err, "line 4: Distance row has 2 columns but Time row has 3");
+
+        let extra = format!("{sheet}\nSpeed:  1  2  3\n");
+        let err = parse_sheet(&extra, Kerning::Spaced).unwrap_err();
+        assert_eq!(err, "line 4: unexpected row after Distance");
+        assert_eq!(
+            parse_sheet("Time: 7\n", Kerning::Spaced),
+            Err("missing Distance row".to_string())
+        );
+    }
+
+    #[test]
+    fn alt_physics_match_brute_force() {
+        for acceleration in 1..4 {
+            for delay in 0..5 {
+                for time in 0..30 {
+                    for record in 0..60 {
+                        let physics = Physics {
+                            acceleration,
+                            delay,
+                        };
+                        let race = Race::new(time, record).with_physics(physics);
+                        let wins: Vec<u64> = race
+                            .distance_curve()
+                            .filter(|(_, d)| *d > record)
+                            .map(|(h, _)| h)
+                            .collect();
+
+                        assert_eq!(race.err_margin(), wins.len() as u64);
+                        if let Some(range) = race.winning_range() {
+                            assert_eq!(*range.start(), wins[0]);
+                            assert_eq!(*range.end(), *wins.last().unwrap());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Hand, RuleSet, TieBreak};
+use std::fmt::Write;
+
+/// Walks through a sorted list of hands, showing for each one its category,
+/// what any wild cards were counted as, how it compares to the hands ranked
+/// either side of it and what it adds to the total winnings.
+pub fn explain(sorted: &[Hand], rules: &RuleSet) -> String {
+    let mut out = String::new();
+    let mut total: u64 = 0;
+    for (idx, hand) in sorted.iter().enumerate() {
+        let rank = idx + 1;
+        let winnings = hand.bid as u64 * rank as u64;
+        total += winnings;
+
+        write!(out, "rank {rank}: {}", labels(&hand.cards, rules)).unwrap();
+        if let Some(val) = hand.wild_as {
+            let as_played: Vec<u8> = hand
+                .cards
+                .iter()
+                .map(|c| if rules.is_wild(*c) { val } else { *c })
+                .collect();
+            let wilds: Vec<String> = hand
+                .cards
+                .iter()
+                .filter(|c| rules.is_wild(**c))
+                .map(|c| format!("{}->{}", rules.card_label(*c), rules.card_label(val)))
+                .collect();
+            write!(
+                out,
+                " ({} = {})",
+                wilds.join(", "),
+                labels(&as_played, rules)
+            )
+            .unwrap();
+        }
+        writeln!(
+            out,
+            " | {} | {} x {rank} = {winnings}",
+            hand.strength, hand.bid
+        )
+        .unwrap();
+
+        if idx > 0 {
+            let below = &sorted[idx - 1];
+            writeln!(
+                out,
+                "    above rank {idx} {}: {}",
+                labels(&below.cards, rules),
+                compare(hand, below, rules)
+            )
+            .unwrap();
+        }
+        if let Some(above) = sorted.get(idx + 1) {
+            writeln!(
+                out,
+                "    below rank {} {}: {}",
+                rank + 1,
+                labels(&above.cards, rules),
+                compare(hand, above, rules)
+            )
+            .unwrap();
+        }
+    }
+    writeln!(out, "total: {total}").unwrap();
+    out
+}
+
+/// Why `hand` sorts where it does relative to `other`.
+fn compare(hand: &Hand, other: &Hand, rules: &RuleSet) -> String {
+    if hand.strength != other.strength {
+        return format!("{} vs {}", hand.strength, other.strength);
+    }
+
+    let diff = hand
+        .tie_key
+        .iter()
+        .zip(other.tie_key.iter())
+        .position(|(a, b)| a != b);
+    let Some(i) = diff else {
+        return format!("both {}, identical tie-break", hand.strength);
+    };
+    let card = match rules.tie_break {
+        TieBreak::CardOrder => "card",
+        TieBreak::SortedRank => "sorted card",
+    };
+    format!(
+        "both {}, {card} {}: {} vs {}",
+        hand.strength,
+        i + 1,
+        rules.card_label(hand.tie_key[i]),
+        rules.card_label(other.tie_key[i])
+    )
+}
+
+fn labels(cards: &[u8], rules: &RuleSet) -> String {
+    cards.iter().map(|c| rules.card_label(*c)).collect()
+}
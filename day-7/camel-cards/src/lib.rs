@@ -0,0 +1,355 @@
+mod explain;
+mod rules;
+
+pub use explain::explain;
+pub use rules::{RuleSet, TieBreak};
+
+#[derive(Debug, Eq)]
+pub struct Hand {
+    pub cards: CardVals,
+    pub strength: HandStrength,
+    pub bid: u32,
+    /// The card every wild card in the hand stands in for, if it has any.
+    pub wild_as: Option<u8>,
+    tie_key: CardVals,
+}
+
+impl Hand {
+    pub fn from_line(line: &'_ str, rules: &RuleSet) -> Result<Self, String> {
+        let (cards, bid) = line
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or(format!("Expected cards and a bid in '{line}'"))?;
+        let bid = bid
+            .trim()
+            .parse::<u32>()
+            .map_err(|_| format!("Invalid bid '{}'", bid.trim()))?;
+        Self::new(cards, bid, rules)
+    }
+
+    pub fn new(cards: &'_ str, bid: u32, rules: &RuleSet) -> Result<Self, String> {
+        let cards = Self::map_card_values(cards, rules)?;
+        let mut counter = CardCounter::new(rules.best_card());
+        for c in cards.iter() {
+            counter.add(*c, rules.is_wild(*c));
+        }
+        let strength = HandStrength::from_tallies(counter.tallies());
+        let tie_key = match rules.tie_break {
+            TieBreak::CardOrder => cards.clone(),
+            TieBreak::SortedRank => counter.sorted_cards(),
+        };
+        let wild_as = if counter.wild.is_empty() {
+            None
+        } else {
+            counter.top_card()
+        };
+        Ok(Self {
+            cards,
+            strength,
+            bid,
+            wild_as,
+            tie_key,
+        })
+    }
+
+    fn map_card_values(cards: &'_ str, rules: &RuleSet) -> Result<CardVals, String> {
+        cards
+            .chars()
+            .map(|c| {
+                rules
+                    .card_value(c)
+                    .ok_or(format!("Card '{c}' is not in the card order"))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Hand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} : {} : {}", self.cards, self.strength, self.bid)
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.strength
+            .cmp(&other.strength)
+            .then_with(|| self.tie_key.cmp(&other.tie_key))
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Hand {
+    fn eq(&self, other: &Self) -> bool {
+        self.strength == other.strength && self.tie_key == other.tie_key
+    }
+}
+
+/// A hand's category, identified by its tally signature: the size of each
+/// group of matching cards, biggest first. Comparing signatures
+/// lexicographically ranks the standard categories in the usual order and
+/// extends to hands of any size, e.g. `[6]` beats `[5, 1]` and `[2, 2, 2]`
+/// beats `[2, 2, 1, 1]`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HandStrength {
+    tallies: Vec<usize>,
+}
+
+impl HandStrength {
+    pub fn from_tallies(tallies: Vec<usize>) -> Self {
+        Self { tallies }
+    }
+
+    pub fn tallies(&self) -> &[usize] {
+        &self.tallies
+    }
+}
+
+impl std::fmt::Display for HandStrength {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let groups: Vec<usize> = self.tallies.iter().copied().filter(|t| *t > 1).collect();
+        match groups[..] {
+            [] => write!(f, "high card"),
+            [2] => write!(f, "pair"),
+            [3, 2] => write!(f, "full house"),
+            [n] => write!(f, "{} of a kind", count_name(n)),
+            _ if groups.iter().all(|g| *g == groups[0]) => {
+                let size = match groups[0] {
+                    2 => "pair".to_string(),
+                    3 => "triple".to_string(),
+                    n => format!("{}-of-a-kind", count_name(n)),
+                };
+                write!(f, "{} {size}", count_name(groups.len()))
+            }
+            _ => {
+                let names: Vec<String> = groups
+                    .iter()
+                    .map(|g| match g {
+                        2 => "pair".to_string(),
+                        n => format!("{} of a kind", count_name(*n)),
+                    })
+                    .collect();
+                write!(f, "{}", names.join(" and "))
+            }
+        }
+    }
+}
+
+fn count_name(n: usize) -> String {
+    const NAMES: [&str; 11] = [
+        "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    ];
+    match NAMES.get(n) {
+        Some(name) => name.to_string(),
+        None => n.to_string(),
+    }
+}
+
+#[derive(Debug)]
+struct CardCounter {
+    tracker: Vec<(u8, usize)>,
+    wild: Vec<u8>,
+    /// What wild cards stand in for when there's nothing else in the hand.
+    best: u8,
+}
+
+impl CardCounter {
+    fn new(best: u8) -> Self {
+        Self {
+            tracker: Vec::new(),
+            wild: Vec::new(),
+            best,
+        }
+    }
+
+    fn add(&mut self, val: u8, wild: bool) {
+        if wild {
+            self.wild.push(val);
+            return;
+        }
+        for set in self.tracker.iter_mut() {
+            if set.0 == val {
+                set.1 += 1;
+                return;
+            }
+        }
+        self.tracker.push((val, 1));
+    }
+
+    /// Card groups biggest first, higher cards first among equal sizes.
+    /// Wild cards join the top group since that always makes the best hand,
+    /// or all become the best card when the hand is nothing but wild cards.
+    fn groups(&self) -> Vec<(u8, usize)> {
+        let mut res = self.tracker.clone();
+        res.sort_by_key(|set| std::cmp::Reverse((set.1, set.0)));
+        match res.first_mut() {
+            Some(top) => top.1 += self.wild.len(),
+            None if !self.wild.is_empty() => res.push((self.best, self.wild.len())),
+            None => (),
+        }
+        res
+    }
+
+    /// The card wild cards turn into, `None` for an empty hand.
+    fn top_card(&self) -> Option<u8> {
+        self.groups().first().map(|set| set.0)
+    }
+
+    fn tallies(&self) -> Vec<usize> {
+        self.groups().iter().map(|set| set.1).collect()
+    }
+
+    fn sorted_cards(&self) -> CardVals {
+        self.groups()
+            .into_iter()
+            .flat_map(|(val, count)| std::iter::repeat_n(val, count))
+            .collect()
+    }
+}
+
+pub type CardVals = Vec<u8>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 5] = [
+        "32T3K 765",
+        "T55J5 684",
+        "KK677 28",
+        "KTJJT 220",
+        "QQQJA 483",
+    ];
+
+    fn winnings(rules: &RuleSet) -> u32 {
+        let mut hands: Vec<Hand> = EXAMPLE
+            .iter()
+            .map(|l| Hand::from_line(l, rules).unwrap())
+            .collect();
+        hands.sort();
+        let mut total = 0;
+        for (idx, hand) in hands.iter().enumerate() {
+            total += hand.bid * (idx as u32 + 1);
+        }
+        total
+    }
+
+    #[test]
+    fn presets() {
+        assert_eq!(winnings(&RuleSet::standard()), 6440);
+        assert_eq!(winnings(&RuleSet::jokers()), 5905);
+    }
+
+    #[test]
+    fn rule_file() {
+        let rules: RuleSet = "order: J23456789TQKA\nwild: J\ntie-break: card-order\n"
+            .parse()
+            .unwrap();
+        assert_eq!(rules, RuleSet::jokers());
+
+        assert!("wild: J".parse::<RuleSet>().is_err());
+        assert!("order: 23A\nwild: J".parse::<RuleSet>().is_err());
+
+        // Values are stored as u8, from 1 up
+        let order: String = (0..255)
+            .map(|i| char::from_u32(0x100 + i).unwrap())
+            .collect();
+        let rules: RuleSet = format!("order: {order}").parse().unwrap();
+        assert_eq!(rules.card_value(order.chars().last().unwrap()), Some(255));
+        assert!(format!("order: {order}!").parse::<RuleSet>().is_err());
+        assert!("order:".parse::<RuleSet>().is_err());
+
+        let rules: RuleSet = "order: 23A".parse().unwrap();
+        assert_eq!(
+            Hand::from_line("2K3 10", &rules),
+            Err("Card 'K' is not in the card order".to_string())
+        );
+        assert!(Hand::from_line("23A", &rules).is_err());
+        assert!(Hand::from_line("23A x", &rules).is_err());
+    }
+
+    #[test]
+    fn sorted_rank_tie_break() {
+        let mut rules = RuleSet::standard();
+        rules.tie_break = TieBreak::SortedRank;
+
+        // Card order would put the 2s first, sorted rank compares the triples
+        let triple_threes = Hand::new("22333", 0, &rules).unwrap();
+        let triple_twos = Hand::new("33222", 0, &rules).unwrap();
+        assert!(triple_threes > triple_twos);
+
+        let mut rules = RuleSet::jokers();
+        rules.tie_break = TieBreak::SortedRank;
+        assert_eq!(
+            Hand::new("JJJJJ", 0, &rules).unwrap().strength.to_string(),
+            "five of a kind"
+        );
+        assert_eq!(
+            Hand::new("KJ2K2", 0, &rules).unwrap(),
+            Hand::new("KKK22", 0, &rules).unwrap()
+        );
+
+        // All jokers count as the aces they stand in for
+        let jokers = Hand::new("JJJJJ", 0, &rules).unwrap();
+        assert_eq!(jokers.wild_as, rules.card_value('A'));
+        assert_eq!(jokers, Hand::new("AAAAA", 0, &rules).unwrap());
+        assert!(jokers > Hand::new("KKKKK", 0, &rules).unwrap());
+    }
+
+    #[test]
+    fn extended_categories() {
+        let rules = RuleSet::jokers();
+        let names: Vec<String> = [
+            "23456", "K2K456", "AAKKQQ", "222333", "222J33", "QQQQQQ", "AAAJJ2",
+        ]
+        .iter()
+        .map(|c| Hand::new(c, 0, &rules).unwrap().strength.to_string())
+        .collect();
+        assert_eq!(
+            names,
+            vec![
+                "high card",
+                "pair",
+                "three pair",
+                "two triple",
+                "four of a kind and pair",
+                "six of a kind",
+                "five of a kind"
+            ]
+        );
+
+        let six_kind = Hand::new("QQQQQQ", 0, &rules).unwrap();
+        let five_kind = Hand::new("AAAAA2", 0, &rules).unwrap();
+        let three_pair = Hand::new("223344", 0, &rules).unwrap();
+        let two_pair = Hand::new("AAKK23", 0, &rules).unwrap();
+        assert!(six_kind > five_kind);
+        assert!(three_pair > two_pair);
+        assert!(Hand::new("AAAA", 0, &rules).unwrap() > Hand::new("KKK2", 0, &rules).unwrap());
+    }
+
+    #[test]
+    fn explain_jokers() {
+        let rules = RuleSet::jokers();
+        let mut hands: Vec<Hand> = EXAMPLE
+            .iter()
+            .map(|l| Hand::from_line(l, &rules).unwrap())
+            .collect();
+        hands.sort();
+        let out = explain(&hands, &rules);
+        let lines: Vec<&str> = out.lines().collect();
+
+        assert_eq!(lines[0], "rank 1: 32T3K | pair | 765 x 1 = 765");
+        assert!(lines.contains(&"rank 3: T55J5 (J->5 = T5555) | four of a kind | 684 x 3 = 2052"));
+        assert!(lines.contains(&"    above rank 3 T55J5: both four of a kind, card 1: Q vs T"));
+        assert_eq!(*lines.last().unwrap(), "total: 5905");
+        assert_eq!(
+            Hand::new("JJJJJ", 0, &rules).unwrap().wild_as,
+            rules.card_value('A')
+        );
+    }
+}
@@ -0,0 +1,134 @@
+use std::str::FromStr;
+
+/// How two hands of the same strength are separated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Compare cards one by one in the order they were dealt.
+    CardOrder,
+    /// Compare the biggest group first, poker style, with wild cards
+    /// counted as the card they stand in for.
+    SortedRank,
+}
+
+impl FromStr for TieBreak {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "card-order" => Ok(Self::CardOrder),
+            "sorted-rank" => Ok(Self::SortedRank),
+            _ => Err(format!("Invalid tie-break '{s}'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuleSet {
+    /// Every card label, weakest first.
+    pub order: Vec<char>,
+    pub wild: Vec<char>,
+    pub tie_break: TieBreak,
+}
+
+impl RuleSet {
+    /// Part 1: no wild cards, J ranks between T and Q.
+    pub fn standard() -> Self {
+        Self {
+            order: "23456789TJQKA".chars().collect(),
+            wild: Vec::new(),
+            tie_break: TieBreak::CardOrder,
+        }
+    }
+
+    /// Part 2: J is wild but ranks below 2 when breaking ties.
+    pub fn jokers() -> Self {
+        Self {
+            order: "J23456789TQKA".chars().collect(),
+            wild: vec!['J'],
+            tie_break: TieBreak::CardOrder,
+        }
+    }
+
+    /// Cards are valued from 1 upwards by their place in `order`.
+    pub fn card_value(&self, c: char) -> Option<u8> {
+        self.order.iter().position(|o| *o == c).map(|i| i as u8 + 1)
+    }
+
+    pub fn card_label(&self, val: u8) -> char {
+        self.order[val as usize - 1]
+    }
+
+    pub fn is_wild(&self, val: u8) -> bool {
+        self.wild.contains(&self.card_label(val))
+    }
+
+    /// The strongest card that isn't wild, for hands made only of wild cards.
+    pub fn best_card(&self) -> u8 {
+        (1..=self.order.len() as u8)
+            .rev()
+            .find(|v| !self.is_wild(*v))
+            .unwrap_or(self.order.len() as u8)
+    }
+}
+
+/// Rule files hold one `key: value` pair per line, e.g.
+///
+/// ```text
+/// order: J23456789TQKA
+/// wild: J
+/// tie-break: card-order
+/// ```
+///
+/// `wild` and `tie-break` may be left out. Cards are valued as `u8`, so
+/// `order` can have at most 255 of them.
+impl FromStr for RuleSet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut order: Option<Vec<char>> = None;
+        let mut wild: Vec<char> = Vec::new();
+        let mut tie_break = TieBreak::CardOrder;
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, val) = line
+                .split_once(':')
+                .ok_or(format!("line {}: expected 'key: value'", i + 1))?;
+            let val = val.trim();
+            match key.trim() {
+                "order" => order = Some(val.chars().collect()),
+                "wild" => wild = val.chars().filter(|c| !c.is_whitespace()).collect(),
+                "tie-break" => tie_break = val.parse()?,
+                k => return Err(format!("line {}: unknown key '{k}'", i + 1)),
+            }
+        }
+
+        let order = order
+            .filter(|o| !o.is_empty())
+            .ok_or("Missing card order".to_string())?;
+        if order.len() > u8::MAX as usize {
+            return Err(format!(
+                "Card order has {} cards, at most {} are allowed",
+                order.len(),
+                u8::MAX
+            ));
+        }
+        for (i, c) in order.iter().enumerate() {
+            if order[..i].contains(c) {
+                return Err(format!("Card '{c}' appears twice in the order"));
+            }
+        }
+        if let Some(c) = wild.iter().find(|c| !order.contains(c)) {
+            return Err(format!("Wild card '{c}' is not in the order"));
+        }
+
+        Ok(Self {
+            order,
+            wild,
+            tie_break,
+        })
+    }
+}
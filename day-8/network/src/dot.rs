@@ -0,0 +1,102 @@
+use crate::{first_visits, Network, NodeId};
+use std::fmt::Write;
+
+const START_COLOUR: &str = "palegreen";
+const END_COLOUR: &str = "lightcoral";
+
+/// Renders a network as a Graphviz digraph, e.g. `dot -Tsvg network.dot`.
+/// Start and end nodes are filled in, and edges are labelled with the
+/// instruction that follows them.
+pub struct DotExport<'a> {
+    network: &'a Network,
+    is_start: &'a [bool],
+    is_end: &'a [bool],
+    visits: Vec<(NodeId, Vec<Option<u64>>)>,
+}
+
+impl<'a> DotExport<'a> {
+    pub fn new(network: &'a Network, is_start: &'a [bool], is_end: &'a [bool]) -> Self {
+        Self {
+            network,
+            is_start,
+            is_end,
+            visits: Vec::new(),
+        }
+    }
+
+    /// Labels every node with the step each ghost first reaches it.
+    pub fn annotate_visits(mut self, instructions: &[usize]) -> Self {
+        self.visits = self
+            .network
+            .ids()
+            .filter(|id| self.is_start[*id as usize])
+            .map(|id| (id, first_visits(self.network, instructions, id)))
+            .collect();
+        self
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::from("digraph network {\n");
+        for id in self.network.ids() {
+            let mut label = escape(self.network.name(id));
+            for (start, visits) in self.visits.iter() {
+                if let Some(step) = visits[id as usize] {
+                    write!(label, "\\n{}@{step}", escape(self.network.name(*start))).unwrap();
+                }
+            }
+
+            write!(out, "    n{id} [label=\"{label}\"").unwrap();
+            if self.is_start[id as usize] {
+                write!(out, ", style=filled, fillcolor={START_COLOUR}").unwrap();
+            } else if self.is_end[id as usize] {
+                write!(out, ", style=filled, fillcolor={END_COLOUR}").unwrap();
+            }
+            out.push_str("];\n");
+        }
+
+        for id in self.network.ids() {
+            let (left, right) = (self.network.next(id, 0), self.network.next(id, 1));
+            if left == right {
+                writeln!(out, "    n{id} -> n{left} [label=\"L/R\"];").unwrap();
+            } else {
+                writeln!(out, "    n{id} -> n{left} [label=\"L\"];").unwrap();
+                writeln!(out, "    n{id} -> n{right} [label=\"R\"];").unwrap();
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+fn escape(name: &'_ str) -> String {
+    name.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        let network =
+            Network::from_lines(["11A = (11B, 11B)", "11B = (11A, 11Z)", "11Z = (11Z, 11Z)"])
+                .unwrap();
+        let is_start = [true, false, false];
+        let is_end = [false, false, true];
+        let dot = DotExport::new(&network, &is_start, &is_end)
+            .annotate_visits(&[0, 1])
+            .render();
+
+        let expected = "digraph network {
+    n0 [label=\"11A\\n11A@0\", style=filled, fillcolor=palegreen];
+    n1 [label=\"11B\\n11A@1\"];
+    n2 [label=\"11Z\\n11A@2\", style=filled, fillcolor=lightcoral];
+    n0 -> n1 [label=\"L/R\"];
+    n1 -> n0 [label=\"L\"];
+    n1 -> n2 [label=\"R\"];
+    n2 -> n2 [label=\"L/R\"];
+}
+";
+        assert_eq!(dot, expected);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{Network, NodeId};
+use common::num::crt;
+
+/// Where a single ghost goes once its walk starts repeating.
+/// A ghost's state is its node plus how far through the instructions it
+/// is, so it must revisit a state within `nodes * instructions` steps.
+#[derive(Debug, PartialEq, Eq)]
+pub struct GhostCycle {
+    /// Steps taken before first entering the cycle.
+    pub prefix: u64,
+    pub length: u64,
+    /// Steps before the cycle that land on an end node. These never repeat.
+    pub prefix_hits: Vec<u64>,
+    /// Steps within the first lap of the cycle that land on an end node.
+    /// Each one repeats every `length` steps.
+    pub cycle_hits: Vec<u64>,
+}
+
+impl GhostCycle {
+    pub fn detect(
+        network: &Network,
+        instructions: &[usize],
+        start: NodeId,
+        is_end: &[bool],
+    ) -> Self {
+        let len = instructions.len();
+        let mut seen = vec![u64::MAX; network.len() * len];
+        let mut hits: Vec<u64> = Vec::new();
+        let mut node = start;
+        let mut step: u64 = 0;
+
+        loop {
+            let idx = (step % len as u64) as usize;
+            let state = node as usize * len + idx;
+            if seen[state] != u64::MAX {
+                let prefix = seen[state];
+                let split = hits.partition_point(|h| *h < prefix);
+                let cycle_hits = hits.split_off(split);
+                return Self {
+                    prefix,
+                    length: step - prefix,
+                    prefix_hits: hits,
+                    cycle_hits,
+                };
+            }
+            seen[state] = step;
+
+            if is_end[node as usize] {
+                hits.push(step);
+            }
+            node = network.next(node, instructions[idx]);
+            step += 1;
+        }
+    }
+
+    pub fn hits_at(&self, step: u128) -> bool {
+        if step < self.prefix as u128 {
+            return self.prefix_hits.contains(&(step as u64));
+        }
+        let length = self.length as u128;
+        self.cycle_hits
+            .iter()
+            .any(|h| *h as u128 % length == step % length)
+    }
+}
+
+/// The step at which a ghost starting from `start` first stands on each
+/// node, walking until its path starts repeating.
+pub fn first_visits(network: &Network, instructions: &[usize], start: NodeId) -> Vec<Option<u64>> {
+    let len = instructions.len();
+    let mut seen = vec![false; network.len() * len];
+    let mut visits: Vec<Option<u64>> = vec![None; network.len()];
+    let mut node = start;
+    let mut step: u64 = 0;
+
+    loop {
+        let idx = (step % len as u64) as usize;
+        let state = node as usize * len + idx;
+        if seen[state] {
+            return visits;
+        }
+        seen[state] = true;
+        visits[node as usize].get_or_insert(step);
+
+        node = network.next(node, instructions[idx]);
+        step += 1;
+    }
+}
+
+/// The first step at which every ghost is on an end node at once, or `None`
+/// if that never happens.
+pub fn earliest_arrival(ghosts: &[GhostCycle]) -> Option<u128> {
+    let Some(longest) = ghosts.iter().max_by_key(|g| g.prefix) else {
+        return Some(0);
+    };
+
+    // Before the longest prefix is done, any answer has to be one of that
+    // ghost's one-off hits.
+    for hit in longest.prefix_hits.iter() {
+        if ghosts.iter().all(|g| g.hits_at(*hit as u128)) {
+            return Some(*hit as u128);
+        }
+    }
+
+    // After that every ghost is cycling, so pick one hit per ghost and solve
+    // the congruences together.
+    let mut residues: Vec<(u128, u128)> = vec![(0, 1)];
+    for ghost in ghosts {
+        let length = ghost.length as u128;
+        let mut next: Vec<(u128, u128)> = Vec::new();
+        for r in residues.iter() {
+            for h in ghost.cycle_hits.iter() {
+                if let Some(c) = crt(*r, (*h as u128, length)) {
+                    next.push(c);
+                }
+            }
+        }
+        next.sort_unstable();
+        next.dedup();
+        residues = next;
+    }
+
+    let min_step = longest.prefix as u128;
+    residues
+        .iter()
+        .filter_map(|(r, m)| {
+            if *r >= min_step {
+                return Some(*r);
+            }
+            let laps = (min_step - r).div_ceil(*m);
+            laps.checked_mul(*m)?.checked_add(*r)
+        })
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ghosts(lines: &[&str]) -> Vec<GhostCycle> {
+        let network = Network::from_lines(&lines[1..]).unwrap();
+        let instructions = crate::parse_instructions(lines[0]).unwrap();
+        let is_end: Vec<bool> = network
+            .ids()
+            .map(|id| network.name(id).ends_with('Z'))
+            .collect();
+        network
+            .ids()
+            .filter(|id| network.name(*id).ends_with('A'))
+            .map(|id| GhostCycle::detect(&network, &instructions, id, &is_end))
+            .collect()
+    }
+
+    #[test]
+    fn example() {
+        let ghosts = ghosts(&[
+            "LR",
+            "11A = (11B, XXX)",
+            "11B = (XXX, 11Z)",
+            "11Z = (11B, XXX)",
+            "22A = (22B, XXX)",
+            "22B = (22C, 22C)",
+            "22C = (22Z, 22Z)",
+            "22Z = (22B, 22B)",
+            "XXX = (XXX, XXX)",
+        ]);
+        assert_eq!(
+            ghosts[0],
+            GhostCycle {
+                prefix: 1,
+                length: 2,
+                prefix_hits: vec![],
+                cycle_hits: vec![2],
+            }
+        );
+        assert_eq!(earliest_arrival(&ghosts), Some(6));
+    }
+
+    #[test]
+    fn offset_cycles() {
+        // 1A is on 1Z at steps 2, 4, 6... and 2A at steps 1, 4, 7...
+        // LCM of the first arrivals would say 2.
+        let ghosts = ghosts(&[
+            "L",
+            "1A = (1B, 1B)",
+            "1B = (1Z, 1Z)",
+            "1Z = (1B, 1B)",
+            "2A = (2Z, 2Z)",
+            "2Z = (2B, 2B)",
+            "2B = (2C, 2C)",
+            "2C = (2Z, 2Z)",
+        ]);
+        assert_eq!(earliest_arrival(&ghosts), Some(4));
+    }
+
+    #[test]
+    fn no_solution() {
+        // 1A only reaches an end on even steps, 3A only on odd steps
+        let ghosts = ghosts(&[
+            "L",
+            "1A = (1B, 1B)",
+            "1B = (1Z, 1Z)",
+            "1Z = (1B, 1B)",
+            "3A = (3Z, 3Z)",
+            "3Z = (3B, 3B)",
+            "3B = (3Z, 3Z)",
+        ]);
+        assert_eq!(earliest_arrival(&ghosts), None);
+    }
+
+    #[test]
+    fn prefix_only_hit() {
+        // 1A passes through 1Z once and never comes back
+        let ghosts = ghosts(&[
+            "L",
+            "1A = (1Z, 1Z)",
+            "1Z = (1B, 1B)",
+            "1B = (1B, 1B)",
+            "2A = (2Z, 2Z)",
+            "2Z = (2Z, 2Z)",
+        ]);
+        assert_eq!(earliest_arrival(&ghosts), Some(1));
+    }
+}
@@ -0,0 +1,165 @@
+mod dot;
+mod ghost;
+mod walk;
+
+pub use dot::DotExport;
+pub use ghost::{earliest_arrival, first_visits, GhostCycle};
+pub use walk::NodeMatch;
+
+use std::collections::HashMap;
+
+pub type NodeId = u32;
+
+/// The desert map with every node name interned to a dense id.
+/// Successors live in one flat table, left at `2 * id` and right at
+/// `2 * id + 1`, so walking never touches a string.
+#[derive(Debug, Default)]
+pub struct Network {
+    names: Vec<String>,
+    ids: HashMap<String, NodeId>,
+    successors: Vec<NodeId>,
+    defined: Vec<bool>,
+}
+
+impl Network {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses `AAA = (BBB, CCC)` lines until the input runs out.
+    pub fn from_lines<I, S>(lines: I) -> Result<Self, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut res = Self::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            let line = line.as_ref();
+            if line.trim().is_empty() {
+                continue;
+            }
+            res.parse_node(line)
+                .map_err(|e| format!("node line {}: {e}", i + 1))?;
+        }
+
+        if let Some(id) = res.defined.iter().position(|d| !d) {
+            return Err(format!("Node '{}' is never defined", res.names[id]));
+        }
+        Ok(res)
+    }
+
+    pub fn parse_node(&mut self, line: &'_ str) -> Result<(), String> {
+        let (key, tup) = line.split_once('=').ok_or("Missing '='")?;
+        let tup = tup
+            .trim()
+            .strip_prefix('(')
+            .and_then(|t| t.strip_suffix(')'))
+            .ok_or("Expected '(left, right)'")?;
+        let (left, right) = tup.split_once(',').ok_or("Expected '(left, right)'")?;
+
+        let key = self.intern(key.trim());
+        let left = self.intern(left.trim());
+        let right = self.intern(right.trim());
+        if self.defined[key as usize] {
+            return Err(format!("Node '{}' is defined twice", self.name(key)));
+        }
+
+        self.successors[2 * key as usize] = left;
+        self.successors[2 * key as usize + 1] = right;
+        self.defined[key as usize] = true;
+        Ok(())
+    }
+
+    fn intern(&mut self, name: &'_ str) -> NodeId {
+        if let Some(id) = self.ids.get(name) {
+            return *id;
+        }
+        let id = self.names.len() as NodeId;
+        self.names.push(name.to_owned());
+        self.ids.insert(name.to_owned(), id);
+        self.successors.extend([id, id]);
+        self.defined.push(false);
+        id
+    }
+
+    pub fn id(&self, name: &'_ str) -> Option<NodeId> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: NodeId) -> &str {
+        &self.names[id as usize]
+    }
+
+    /// Follows one instruction, 0 for left and 1 for right.
+    pub fn next(&self, id: NodeId, instruction: usize) -> NodeId {
+        self.successors[2 * id as usize + instruction]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn ids(&self) -> impl Iterator<Item = NodeId> {
+        0..self.names.len() as NodeId
+    }
+}
+
+pub fn parse_instructions(line: &'_ str) -> Result<Vec<usize>, String> {
+    let mut res: Vec<usize> = Vec::new();
+    for c in line.trim().chars() {
+        res.push(match c {
+            'L' => 0,
+            'R' => 1,
+            _ => return Err(format!("Invalid instruction '{c}'")),
+        });
+    }
+    if res.is_empty() {
+        return Err("No instructions".to_string());
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interning() {
+        let lines = ["AAA = (BBB, BBB)", "BBB = (AAA, ZZZ)", "ZZZ = (ZZZ, ZZZ)"];
+        let network = Network::from_lines(lines).unwrap();
+        assert_eq!(network.len(), 3);
+
+        let aaa = network.id("AAA").unwrap();
+        let bbb = network.next(aaa, 0);
+        assert_eq!(network.name(bbb), "BBB");
+        assert_eq!(network.name(network.next(bbb, 1)), "ZZZ");
+    }
+
+    #[test]
+    fn long_names() {
+        let lines = ["start = (middle, start)", "middle = (X, X)", "X = (X, X)"];
+        let network = Network::from_lines(lines).unwrap();
+        let start = network.id("start").unwrap();
+        assert_eq!(network.name(network.next(start, 0)), "middle");
+        assert_eq!(network.next(start, 1), start);
+    }
+
+    #[test]
+    fn undefined_node() {
+        let err = Network::from_lines(["AAA = (BBB, CCC)"]).unwrap_err();
+        assert_eq!(err, "Node 'BBB' is never defined");
+        assert!(Network::from_lines(["AAA (BBB, CCC)"]).is_err());
+    }
+
+    #[test]
+    fn instructions() {
+        assert_eq!(parse_instructions("LRL\n"), Ok(vec![0, 1, 0]));
+        assert_eq!(parse_instructions(""), Err("No instructions".to_string()));
+        assert!(parse_instructions("   ").is_err());
+        assert!(parse_instructions("LXR").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{Network, NodeId};
+use std::str::FromStr;
+
+/// Picks out start or end nodes by name.
+///
+/// Parsed from the command line as `AAA` for an exact name, `*Z` for a
+/// suffix, `A*` for a prefix, or `AAA,BBB` for an explicit list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NodeMatch {
+    Exact(String),
+    Prefix(String),
+    Suffix(String),
+    List(Vec<String>),
+}
+
+impl NodeMatch {
+    pub fn matches(&self, name: &'_ str) -> bool {
+        match self {
+            Self::Exact(n) => name == n,
+            Self::Prefix(p) => name.starts_with(p.as_str()),
+            Self::Suffix(s) => name.ends_with(s.as_str()),
+            Self::List(names) => names.iter().any(|n| n == name),
+        }
+    }
+}
+
+impl FromStr for NodeMatch {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("Empty node pattern".to_string());
+        }
+        if s.contains(',') {
+            return Ok(Self::List(
+                s.split(',').map(|n| n.trim().to_owned()).collect(),
+            ));
+        }
+        if let Some(suffix) = s.strip_prefix('*') {
+            return Ok(Self::Suffix(suffix.to_owned()));
+        }
+        if let Some(prefix) = s.strip_suffix('*') {
+            return Ok(Self::Prefix(prefix.to_owned()));
+        }
+        Ok(Self::Exact(s.to_owned()))
+    }
+}
+
+impl Network {
+    /// One flag per node id, set where the node matches `pattern`.
+    pub fn mark(&self, pattern: &NodeMatch) -> Vec<bool> {
+        self.ids()
+            .map(|id| pattern.matches(self.name(id)))
+            .collect()
+    }
+
+    /// Every node visited from `start` up to and including the first end
+    /// node, so the step count is one less than the path length. `None` if
+    /// the walk starts looping without ever reaching an end.
+    pub fn walk(
+        &self,
+        instructions: &[usize],
+        start: NodeId,
+        is_end: &[bool],
+    ) -> Option<Vec<NodeId>> {
+        let len = instructions.len();
+        let mut seen = vec![false; self.len() * len];
+        let mut path = vec![start];
+        let mut node = start;
+
+        while !is_end[node as usize] {
+            let idx = (path.len() - 1) % len;
+            let state = node as usize * len + idx;
+            if seen[state] {
+                return None;
+            }
+            seen[state] = true;
+
+            node = self.next(node, instructions[idx]);
+            path.push(node);
+        }
+        Some(path)
+    }
+
+    /// A walk from every node matching `start`, in id order, each paired
+    /// with the node it began at.
+    pub fn walk_all(
+        &self,
+        instructions: &[usize],
+        start: &NodeMatch,
+        is_end: &[bool],
+    ) -> Vec<(NodeId, Option<Vec<NodeId>>)> {
+        self.ids()
+            .filter(|id| start.matches(self.name(*id)))
+            .map(|id| (id, self.walk(instructions, id, is_end)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns() {
+        let parse = |s: &str| s.parse::<NodeMatch>().unwrap();
+        assert_eq!(parse("AAA"), NodeMatch::Exact("AAA".to_string()));
+        assert!(parse("*Z").matches("11Z"));
+        assert!(!parse("*Z").matches("Z11"));
+        assert!(parse("11*").matches("11A"));
+        assert!(parse("AAA, BBB").matches("BBB"));
+        assert!(!parse("AAA,BBB").matches("CCC"));
+    }
+
+    #[test]
+    fn walk_paths() {
+        let network = Network::from_lines([
+            "AAA = (BBB, BBB)",
+            "BBB = (AAA, ZZZ)",
+            "ZZZ = (ZZZ, ZZZ)",
+            "LOOP = (LOOP, LOOP)",
+        ])
+        .unwrap();
+        let instructions = [0, 0, 1];
+        let is_end = network.mark(&"ZZZ".parse().unwrap());
+
+        let path = network
+            .walk(&instructions, network.id("AAA").unwrap(), &is_end)
+            .unwrap();
+        let names: Vec<&str> = path.iter().map(|id| network.name(*id)).collect();
+        assert_eq!(names, vec!["AAA", "BBB", "AAA", "BBB", "AAA", "BBB", "ZZZ"]);
+
+        assert_eq!(
+            network.walk(&instructions, network.id("LOOP").unwrap(), &is_end),
+            None
+        );
+
+        let walks = network.walk_all(&instructions, &"XYZ".parse().unwrap(), &is_end);
+        assert!(walks.is_empty());
+        let walks = network.walk_all(&instructions, &"AAA,LOOP".parse().unwrap(), &is_end);
+        assert_eq!(walks.len(), 2);
+        assert_eq!(walks[0].0, network.id("AAA").unwrap());
+        assert_eq!(walks[0].1.as_ref().map(|p| p.len()), Some(7));
+        assert_eq!(walks[1], (network.id("LOOP").unwrap(), None));
+    }
+}
@@ -1,59 +1,55 @@
-use core::panic;
-use std::collections::HashMap;
+use network::{parse_instructions, Network, NodeMatch};
 use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut path = String::new();
+    let mut start = NodeMatch::Exact("AAA".to_string());
+    let mut end = NodeMatch::Exact("ZZZ".to_string());
+    let mut show_path = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--start" => start = args.next().unwrap().parse().unwrap(),
+            "--end" => end = args.next().unwrap().parse().unwrap(),
+            "--path" => show_path = true,
+            _ => path = arg,
+        }
+    }
 
-    let f = File::open(&args[1]).unwrap();
+    let f = File::open(&path).unwrap();
     let mut input = BufReader::new(f);
 
     let mut buf = String::new();
     input.read_line(&mut buf).unwrap();
-    let instructions = parse_instructions(&buf);
-    input.read_line(&mut buf).unwrap();
+    let instructions = parse_instructions(&buf).unwrap();
 
-    let mut network: Network = HashMap::new();
-    for line in input.lines() {
-        parse_node(&mut network, &line.unwrap());
-    }
+    let network = Network::from_lines(input.lines().map(|l| l.unwrap())).unwrap();
+    let is_end = network.mark(&end);
 
-    let mut node = "AAA";
-    let mut count = 0;
-    for i in instructions {
-        if node == "ZZZ" {
-            break;
-        }
-        count += 1;
-        node = &network.get(node).unwrap()[i];
+    let walks = network.walk_all(&instructions, &start, &is_end);
+    if walks.is_empty() {
+        eprintln!("Start pattern matched no nodes");
+        std::process::exit(1);
     }
+    let many = walks.len() > 1;
+    for (from, walk) in walks.iter() {
+        let name = network.name(*from);
+        let Some(walk) = walk else {
+            println!("{name}: never reaches an end node");
+            continue;
+        };
+        if show_path {
+            let names: Vec<&str> = walk.iter().map(|id| network.name(*id)).collect();
+            println!("{}", names.join(" -> "));
+        }
 
-    println!("Answer: {count}");
-}
-
-fn parse_node(network: &mut Network, line: &'_ str) {
-    let mut line = line.split('=');
-    let key = line.next().unwrap().trim().to_owned();
-
-    let tup = line.next().unwrap().trim();
-    let left = tup[1..=3].to_owned();
-    let right = tup[6..=8].to_owned();
-
-    network.insert(key, [left, right]);
-}
-
-fn parse_instructions(line: &'_ str) -> std::iter::Cycle<std::vec::IntoIter<usize>> {
-    let mut res: Vec<usize> = Vec::new();
-    for c in line.trim().chars() {
-        res.push(match c {
-            'L' => 0,
-            'R' => 1,
-            _ => panic!(),
-        });
+        let count = walk.len() - 1;
+        if many {
+            println!("{name}: {count}");
+        } else {
+            println!("Answer: {count}");
+        }
     }
-    res.into_iter().cycle()
 }
-
-type Network = HashMap<String, [String; 2]>;
@@ -1,68 +1,61 @@
-use core::panic;
-use std::collections::HashMap;
+use network::{parse_instructions, Network, NodeMatch};
 use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut path = String::new();
+    let mut start = NodeMatch::Suffix("A".to_string());
+    let mut end = NodeMatch::Suffix("Z".to_string());
+    let mut show_path = false;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--start" => start = args.next().unwrap().parse().unwrap(),
+            "--end" => end = args.next().unwrap().parse().unwrap(),
+            "--path" => show_path = true,
+            _ => path = arg,
+        }
+    }
 
-    let f = File::open(&args[1]).unwrap();
+    let f = File::open(&path).unwrap();
     let mut input = BufReader::new(f);
 
     let mut buf = String::new();
     input.read_line(&mut buf).unwrap();
-    let instructions = parse_instructions(&buf);
-    input.read_line(&mut buf).unwrap();
-
-    let mut network: Network = HashMap::new();
-    for line in input.lines() {
-        parse_node(&mut network, &line.unwrap());
-    }
-
-    let mut nodes = Vec::new();
-    for key in network.keys() {
-        if key.chars().nth_back(0).unwrap() == 'A' {
-            nodes.push(key);
+    let instructions = parse_instructions(&buf).unwrap();
+
+    let network = Network::from_lines(input.lines().map(|l| l.unwrap())).unwrap();
+    let is_end = network.mark(&end);
+
+    if show_path {
+        // Each ghost's own way to its first end node
+        for (from, walk) in network.walk_all(&instructions, &start, &is_end) {
+            match walk {
+                Some(walk) => {
+                    let names: Vec<&str> = walk.iter().map(|id| network.name(*id)).collect();
+                    println!("{}", names.join(" -> "));
+                }
+                None => println!("{}: never reaches an end node", network.name(from)),
+            }
         }
     }
 
-    let mut count = 0;
-    for i in instructions {
-        println!("{nodes:?}");
-        if nodes.iter().all(|&x| x.chars().nth_back(0).unwrap() == 'Z') {
+    let mut nodes: Vec<_> = network
+        .ids()
+        .filter(|id| start.matches(network.name(*id)))
+        .collect();
+
+    let mut count: u64 = 0;
+    for i in instructions.iter().cycle() {
+        if nodes.iter().all(|&x| is_end[x as usize]) {
             break;
         }
         count += 1;
-        for idx in 0..nodes.len() {
-            nodes[idx] = &network.get(nodes[idx]).unwrap()[i];
+        for node in nodes.iter_mut() {
+            *node = network.next(*node, *i);
         }
     }
 
     println!("Answer: {count}");
 }
-
-fn parse_node(network: &mut Network, line: &'_ str) {
-    let mut line = line.split('=');
-    let key = line.next().unwrap().trim().to_owned();
-
-    let tup = line.next().unwrap().trim();
-    let left = tup[1..=3].to_owned();
-    let right = tup[6..=8].to_owned();
-
-    network.insert(key, [left, right]);
-}
-
-fn parse_instructions(line: &'_ str) -> std::iter::Cycle<std::vec::IntoIter<usize>> {
-    let mut res: Vec<usize> = Vec::new();
-    for c in line.trim().chars() {
-        res.push(match c {
-            'L' => 0,
-            'R' => 1,
-            _ => panic!(),
-        });
-    }
-    res.into_iter().cycle()
-}
-
-type Network = HashMap<String, [String; 2]>;
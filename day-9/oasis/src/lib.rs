@@ -0,0 +1,262 @@
+mod rational;
+
+pub use rational::Rational;
+
+/// A history reduced to its leading forward differences, `f(0)`, `Δf(0)`,
+/// `Δ²f(0)` and so on. Newton's forward-difference formula
+/// `f(n) = Σ C(n, j) Δʲf(0)` then gives the value at any position, before
+/// or after the recorded readings, without rebuilding the difference table.
+///
+/// The table stops at the first all-zero row, which also gives the degree
+/// of the polynomial behind the history. If it never gets there, the only
+/// polynomial that fits is the one through every reading, and nothing is
+/// left over to confirm it, so the sequence is flagged as underdetermined.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sequence {
+    diffs: Vec<i128>,
+    len: usize,
+    underdetermined: bool,
+}
+
+impl Sequence {
+    pub fn new(history: &[i64]) -> Result<Self, String> {
+        if history.is_empty() {
+            return Err("Empty history".to_string());
+        }
+
+        let mut row: Vec<i128> = history.iter().map(|x| *x as i128).collect();
+        let mut diffs = Vec::new();
+        while !row.iter().all(|x| *x == 0) {
+            diffs.push(row[0]);
+            if row.len() == 1 {
+                return Ok(Self {
+                    diffs,
+                    len: history.len(),
+                    underdetermined: true,
+                });
+            }
+
+            let mut next = Vec::with_capacity(row.len() - 1);
+            for pair in row.windows(2) {
+                next.push(pair[1].checked_sub(pair[0]).ok_or(OVERFLOW)?);
+            }
+            row = next;
+        }
+        Ok(Self {
+            diffs,
+            len: history.len(),
+            underdetermined: false,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Degree of the fitted polynomial. An all-zero history counts as
+    /// degree 0.
+    pub fn degree(&self) -> usize {
+        self.diffs.len().saturating_sub(1)
+    }
+
+    pub fn is_underdetermined(&self) -> bool {
+        self.underdetermined
+    }
+
+    /// Coefficients of the fitted polynomial in `x`, lowest power first,
+    /// where `x` is the position with the first reading at 0.
+    pub fn coefficients(&self) -> Result<Vec<Rational>, String> {
+        // Expand each C(x, j) = x(x - 1)...(x - j + 1) / j! into powers of x
+        let mut res = vec![Rational::from(0); self.diffs.len().max(1)];
+        let mut falling: Vec<i128> = vec![1];
+        let mut factorial: i128 = 1;
+        for (j, diff) in self.diffs.iter().enumerate() {
+            if j > 0 {
+                let shift = j as i128 - 1;
+                let mut next = vec![0; falling.len() + 1];
+                for (i, c) in falling.iter().enumerate() {
+                    let scaled = c.checked_mul(shift).ok_or(OVERFLOW)?;
+                    next[i + 1] = c.checked_add(next[i + 1]).ok_or(OVERFLOW)?;
+                    next[i] = next[i].checked_sub(scaled).ok_or(OVERFLOW)?;
+                }
+                falling = next;
+                factorial = factorial.checked_mul(j as i128).ok_or(OVERFLOW)?;
+            }
+
+            for (i, c) in falling.iter().enumerate() {
+                let num = c.checked_mul(*diff).ok_or(OVERFLOW)?;
+                let term = Rational::new(num, factorial);
+                res[i] = res[i].checked_add(term).ok_or(OVERFLOW)?;
+            }
+        }
+        Ok(res)
+    }
+
+    /// The value at position `n`, where the history covers `0..len`.
+    pub fn value_at(&self, n: i128) -> Result<i128, String> {
+        let mut total: i128 = 0;
+        let mut binomial: i128 = 1;
+        for (j, diff) in self.diffs.iter().enumerate() {
+            if j > 0 {
+                // C(n, j) = C(n, j - 1) * (n - j + 1) / j, which always
+                // divides exactly
+                let j = j as i128;
+                let factor = n.checked_sub(j - 1).ok_or(OVERFLOW)?;
+                binomial = binomial.checked_mul(factor).ok_or(OVERFLOW)? / j;
+            }
+            if binomial == 0 {
+                // Every later term has the same zero factor
+                break;
+            }
+            let term = binomial.checked_mul(*diff).ok_or(OVERFLOW)?;
+            total = total.checked_add(term).ok_or(OVERFLOW)?;
+        }
+        Ok(total)
+    }
+
+    /// Degree and coefficients in one line, e.g.
+    /// `degree 2, coefficients [1, 3/2, 1/2]`.
+    pub fn describe(&self) -> String {
+        let coefficients = match self.coefficients() {
+            Ok(c) => c
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+            Err(e) => e,
+        };
+        let flag = if self.underdetermined {
+            " (underdetermined)"
+        } else {
+            ""
+        };
+        format!(
+            "degree {}{flag}, coefficients [{coefficients}]",
+            self.degree()
+        )
+    }
+
+    /// The value `k` steps after the last reading.
+    pub fn forward(&self, k: u64) -> Result<i128, String> {
+        self.value_at(self.len() as i128 - 1 + k as i128)
+    }
+
+    /// The value `k` steps before the first reading.
+    pub fn backward(&self, k: u64) -> Result<i128, String> {
+        self.value_at(-(k as i128))
+    }
+}
+
+const OVERFLOW: &str = "Extrapolation overflowed i128";
+
+pub fn parse_line(line: &'_ str) -> Result<Vec<i64>, String> {
+    line.split_whitespace()
+        .map(|x| {
+            x.parse::<i64>()
+                .map_err(|_| format!("Invalid reading '{x}'"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&str; 3] = ["0 3 6 9 12 15", "1 3 6 10 15 21", "10 13 16 21 30 45"];
+
+    fn sequences() -> Vec<Sequence> {
+        EXAMPLE
+            .iter()
+            .map(|l| Sequence::new(&parse_line(l).unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn one_step() {
+        let next: Vec<i128> = sequences().iter().map(|s| s.forward(1).unwrap()).collect();
+        let prev: Vec<i128> = sequences().iter().map(|s| s.backward(1).unwrap()).collect();
+        assert_eq!(next, vec![18, 28, 68]);
+        assert_eq!(prev, vec![-3, 0, 5]);
+    }
+
+    #[test]
+    fn many_steps() {
+        let squares = Sequence::new(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(squares.forward(0).unwrap(), 9);
+        assert_eq!(squares.forward(7).unwrap(), 100);
+        assert_eq!(squares.backward(5).unwrap(), 25);
+        assert_eq!(
+            squares.forward(1_000_000_000).unwrap(),
+            1_000_000_003i128.pow(2)
+        );
+    }
+
+    #[test]
+    fn overflow_is_reported() {
+        let big = Sequence::new(&[i64::MIN, i64::MAX, i64::MIN]).unwrap();
+        // Fine in i128 even though the differences don't fit in i64
+        assert_eq!(
+            big.forward(1).unwrap(),
+            i64::MIN as i128 - 3 * u64::MAX as i128
+        );
+        let cubes = Sequence::new(&[0, 1, 8, 27, 64]).unwrap();
+        assert!(cubes.forward(u64::MAX).is_err());
+    }
+
+    #[test]
+    fn degree_analysis() {
+        let seqs = sequences();
+        assert_eq!(seqs[0].degree(), 1);
+        assert_eq!(seqs[1].degree(), 2);
+        assert_eq!(seqs[2].degree(), 3);
+        assert!(seqs.iter().all(|s| !s.is_underdetermined()));
+
+        let zeros = Sequence::new(&[0, 0, 0]).unwrap();
+        assert_eq!(zeros.degree(), 0);
+        assert_eq!(zeros.forward(3).unwrap(), 0);
+        assert_eq!(zeros.coefficients().unwrap(), vec![Rational::from(0)]);
+
+        let powers = Sequence::new(&[1, 2, 4, 8, 16]).unwrap();
+        assert_eq!(powers.degree(), 4);
+        assert!(powers.is_underdetermined());
+
+        // Confirmed by a single leftover reading
+        let line = Sequence::new(&[2, 5, 8]).unwrap();
+        assert_eq!(line.degree(), 1);
+        assert!(!line.is_underdetermined());
+    }
+
+    #[test]
+    fn coefficients() {
+        // 1 3 6 10 15 21 is (x + 1)(x + 2) / 2
+        let triangle = &sequences()[1];
+        let coeffs: Vec<String> = triangle
+            .coefficients()
+            .unwrap()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(coeffs, vec!["1", "3/2", "1/2"]);
+        assert_eq!(triangle.describe(), "degree 2, coefficients [1, 3/2, 1/2]");
+        assert_eq!(
+            Sequence::new(&[1, 2, 4]).unwrap().describe(),
+            "degree 2 (underdetermined), coefficients [1, 1/2, 1/2]"
+        );
+
+        let cubic = Sequence::new(&[-4, -2, 8, 32, 76]).unwrap();
+        let coeffs = cubic.coefficients().unwrap();
+        assert_eq!(
+            coeffs,
+            vec![
+                Rational::from(-4),
+                Rational::from(0),
+                Rational::from(1),
+                Rational::from(1)
+            ]
+        );
+    }
+}
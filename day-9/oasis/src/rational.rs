@@ -0,0 +1,54 @@
+use common::num::gcd;
+
+/// An exact fraction, always stored in lowest terms with a positive
+/// denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    /// Panics if `den` is 0.
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "Zero denominator");
+        let g = gcd(num.unsigned_abs(), den.unsigned_abs()) as i128;
+        let sign = if den < 0 { -1 } else { 1 };
+        Self {
+            num: sign * num / g,
+            den: sign * den / g,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.den
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        let g = gcd(self.den as u128, rhs.den as u128) as i128;
+        let left = self.num.checked_mul(rhs.den / g)?;
+        let right = rhs.num.checked_mul(self.den / g)?;
+        let den = (self.den / g).checked_mul(rhs.den)?;
+        Some(Self::new(left.checked_add(right)?, den))
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Self { num: n, den: 1 }
+    }
+}
+
+impl std::fmt::Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.den == 1 {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}